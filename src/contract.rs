@@ -1,29 +1,53 @@
 use std::collections::HashMap;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use crate::state::{
-    CONFIG, LIST, MESSAGES, MESSAGES_IDS, NFTS, NFT_RATE_ATH, NFT_RATE_ATL, NFT_RATE_COUNTS,
-    NFT_RATE_DAY_ATH, NFT_RATE_DAY_ATL, NFT_RATINGS, STATS, USER_SAVED, USER_STATS,
+    ACTIVITY_SEQ, BANNED_WORDS, BOOST_BIDS, CHAINS, COLLECTIONS, CONFIG, DIRECT_MESSAGES,
+    DIRECT_MESSAGE_SEQ, DM_BLOCKS, EARLY_RATINGS, FOLLOWERS, FOLLOWING, FOLLOW_COUNTS, GALLERIES,
+    LAST_MESSAGE_ID, LEADERBOARD_SEED, LIST, MESSAGES, MESSAGES_IDS, MESSAGE_REACTIONS,
+    MESSAGE_REPLIES, MESSAGE_REPORTS, MESSAGE_REPORT_COUNTS, NFTS, NFT_MESSAGES, NFT_RATE_ATH,
+    NFT_RATE_ATL, NFT_RATE_COUNTS, NFT_RATE_DAY_ATH, NFT_RATE_DAY_ATL, NFT_RATINGS, NFT_SAVERS,
+    NFT_SAVE_COUNTS, NFT_SAVE_TOP, NOTIFICATIONS, NOTIFICATIONS_READ, NOTIFICATION_SEQ, PAUSED,
+    PROFILES, PROFILE_NAMES, PURGES, REPUTATION_SETTLED, SAVED_COLLECTIONS, SAVED_GALLERIES,
+    SAVED_NFTS, SAVED_VISIBILITY, SCHEDULE_CURRENT, SCHEDULE_PENDING, SCHEDULE_SEQ, STATS,
+    USER_ACTIVE_DAYS, USER_ACTIVITY, USER_BADGES, USER_BANS, USER_BOARD_WINDOWS,
+    USER_CONVERSATIONS, USER_RANKS, USER_SAVED, USER_SAVED_NFTS, USER_SCORES, USER_STATS,
 };
 use crate::types::{
-    Config, ConfigHr, ListKind, ListSort, Message, Nft, Rate, RateCount, RateCounts, TokenUri,
-    TotalStats, UserStats, DAY_IN_SECONDS, DEFAULT_RATE_DECAY, DEFAULT_UNLOCK_GRAFFITI,
-    DEFAULT_UNLOCK_MESSAGES, DEFAULT_UNLOCK_SHARES, DEFAULT_USER_MAX_SHARES, MAX_LEN_ALL_TIME,
-    MAX_LEN_DAY, MAX_LEN_MESSAGE,
+    Action, Activity, ActivityKind, Avatar, Badge, BadgeKind, BoostBid, CollectionStatus, Config,
+    ConfigHr, Conversation, DirectMessage, FollowCounts, Gallery, LeaderboardPeriod,
+    LeaderboardSeed, ListKind, ListOrder, ListSort, Message, MessageId, MessageMeta,
+    MessageResponse, Nft, NftInfo, Notification, NotificationKind, PendingNft, Profile,
+    PurgeCursor, Rate, RateCount, RateCounts, RatingInfo, ReactionCount, Report, SavedCollection,
+    SavedMeta, SavedNft, SchedulePriority, ScheduledNft, TokenUri, TotalStats, Unlock,
+    UnlockProgress, UserBan, UserMetric, UserStats, UserStatsResponse, Visibility,
+    BADGE_BACKFILL_LIMIT, BADGE_RATINGS_MILESTONE, BADGE_STREAK_DAYS, BADGE_TOP_SHARE_RANK,
+    BOARD_PRUNE_LIMIT, CALENDAR_DAY_IN_SECONDS, DAY_IN_SECONDS, DEFAULT_BOOST_DURATION,
+    DEFAULT_LIMIT, DEFAULT_MAX_META_SIZE, DEFAULT_MESSAGE_EDIT_WINDOW, DEFAULT_RATE_DECAY,
+    DEFAULT_REACTIONS, DEFAULT_REPORT_THRESHOLD, DEFAULT_SAVED_COLLECTION, DEFAULT_SLOT_DURATION,
+    DEFAULT_UNLOCK_DMS, DEFAULT_UNLOCK_GRAFFITI, DEFAULT_UNLOCK_MESSAGES, DEFAULT_UNLOCK_SHARES,
+    DEFAULT_USER_MAX_SHARES, LEADERBOARD_MONTH_IN_SECONDS, MAX_FEED_SOURCES, MAX_LEN_ALL_TIME,
+    MAX_LEN_BANNED_WORD, MAX_LEN_BIO, MAX_LEN_COLLECTION_NAME, MAX_LEN_DAY, MAX_LEN_DIRECT_MESSAGE,
+    MAX_LEN_DISPLAY_NAME, MAX_LEN_GALLERY_DESCRIPTION, MAX_LEN_GALLERY_TITLE, MAX_LEN_MESSAGE,
+    MAX_LEN_META_URL, MAX_LEN_REACTION, MAX_LIMIT, MAX_META_MENTIONS, MAX_REACTIONS,
+    MAX_SAVED_COLLECTIONS, MAX_SAVED_PER_COLLECTION, MIN_LEN_DISPLAY_NAME, REPUTATION_ACCURATE,
+    REPUTATION_CLOSE, REPUTATION_EARLY_RATERS, REPUTATION_SETTLE_RATINGS, REPUTATION_SHARE_BONUS,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    has_coins, to_binary, Addr, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdResult, SubMsg,
+    from_json, has_coins, to_json_binary, Addr, Api, BankMsg, Binary, Deps, DepsMut, Empty, Env,
+    Event, MessageInfo, Order, Response, StdError, StdResult, Storage, SubMsg,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw_storage_plus::Bound;
 
 // version info for migration info
 const CONTRACT_NAME: &str = "nft-hop";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+// Release that added the user boards, migrating from anything older runs the one time rewrites
+const BOARDS_VERSION: &str = "0.2.0";
 
 fn compare_desc(a: &(String, RateCount), b: &(String, RateCount)) -> std::cmp::Ordering {
     let avg_a: u128 = u128::from(a.1.sum) * u128::from(b.1.total);
@@ -36,690 +60,3773 @@ fn compare_asc(a: &(String, RateCount), b: &(String, RateCount)) -> std::cmp::Or
     avg_a.cmp(&avg_b)
 }
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(
-    deps: DepsMut,
-    _env: Env,
-    info: MessageInfo,
-    msg: InstantiateMsg,
-) -> Result<Response, ContractError> {
-    let state = Config {
-        owner: info.sender,
-        share_fee: msg.share_fee.clone(),
-        save_fee: msg.save_fee,
+fn check_not_paused(storage: &dyn Storage, action: Action) -> Result<(), ContractError> {
+    let paused = PAUSED.may_load(storage)?.unwrap_or_default();
+    if paused.contains(&action) {
+        return Err(ContractError::Paused { action });
+    }
+    Ok(())
+}
 
-        unlock_messages: Some(DEFAULT_UNLOCK_MESSAGES),
-        unlock_graffiti: Some(DEFAULT_UNLOCK_GRAFFITI),
-        unlock_share: Some(DEFAULT_UNLOCK_SHARES),
-        max_shares: Some(DEFAULT_USER_MAX_SHARES),
-        rate_decay: Some(DEFAULT_RATE_DECAY),
-    };
-    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    CONFIG.save(deps.storage, &state)?;
+fn check_not_banned(storage: &dyn Storage, addr: &Addr, ts: u64) -> Result<(), ContractError> {
+    if let Some(ban) = USER_BANS.may_load(storage, addr.clone())? {
+        match ban.until {
+            None => return Err(ContractError::Banned {}),
+            Some(until) if until > ts => return Err(ContractError::Muted { until }),
+            _ => {}
+        }
+    }
+    Ok(())
+}
 
-    let stats = TotalStats {
-        nfts: 0,
-        ratings: 0,
-        messages: 0,
-        saves: 0,
-    };
-    STATS.save(deps.storage, &stats)?;
+// Remove a message from storage & its NFT cache, along with any reports against it
+fn delete_message(storage: &mut dyn Storage, id: u64) -> Result<Option<Message>, ContractError> {
+    let msg = MESSAGES.may_load(storage, id)?;
+    MESSAGES.remove(storage, id);
+    if let Some(msg) = msg.clone() {
+        NFT_MESSAGES.remove(storage, (msg.class_id, id));
+        // messages still waiting on SeedLeaderboards were never counted
+        let unseeded = LEADERBOARD_SEED.may_load(storage)?.is_some_and(|seed| {
+            id <= seed.messages_until && !matches!(seed.messages_after, Some(after) if id <= after)
+        });
+        if !unseeded {
+            bump_user_score(storage, UserMetric::Messages, &msg.from, msg.ts, false)?;
+        }
+    }
 
-    Ok(Response::new().add_attribute("method", "instantiate"))
-}
+    let reporters = MESSAGE_REPORTS
+        .prefix(id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    for addr in reporters.into_iter() {
+        MESSAGE_REPORTS.remove(storage, (id, addr));
+    }
+    MESSAGE_REPORT_COUNTS.remove(storage, id);
 
-#[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: ExecuteMsg,
-) -> Result<Response, ContractError> {
-    match msg {
-        ExecuteMsg::ChangeConfig { config } => change_config(deps, info, config),
-        ExecuteMsg::Message {
-            class_id,
-            message,
-            meta,
-        } => send_message(deps, env, info, class_id, message, meta),
-        ExecuteMsg::Rate { class_id, v } => rate(deps, env, info, class_id, v),
-        ExecuteMsg::Share {
-            class_id,
-            token,
-            chain_id,
-        } => share(deps, env, info, class_id, token, chain_id),
-        ExecuteMsg::Save { class_id } => save(deps, env, info, class_id),
-        ExecuteMsg::Unsave { class_id } => unsave(deps, env, info, class_id),
-        ExecuteMsg::RemoveMessage { id } => remove_message(deps, info, id),
-        ExecuteMsg::Withdraw { receiver } => withdraw(deps, env, info, receiver),
+    // replies stay up as orphans, only the thread links go
+    if let Some(parent_id) = msg.as_ref().and_then(|m| m.reply_to) {
+        MESSAGE_REPLIES.remove(storage, (parent_id, id));
+    }
+    let replies = MESSAGE_REPLIES
+        .prefix(id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<u64>>>()?;
+    for reply_id in replies.into_iter() {
+        MESSAGE_REPLIES.remove(storage, (id, reply_id));
     }
+    let reactions = MESSAGE_REACTIONS
+        .sub_prefix(id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Addr)>>>()?;
+    for (reaction, addr) in reactions.into_iter() {
+        MESSAGE_REACTIONS.remove(storage, (id, reaction, addr));
+    }
+
+    Ok(msg)
 }
 
-// Update configurations
-pub fn change_config(
-    deps: DepsMut,
-    info: MessageInfo,
-    config: ConfigHr,
-) -> Result<Response, ContractError> {
-    let c: Config = CONFIG.load(deps.storage)?;
-    // Only owner can do this
-    if info.sender != c.owner {
-        return Err(ContractError::Unauthorized {});
+// Length is counted in chars so emoji & non-latin scripts get the full limit
+fn validate_message(storage: &dyn Storage, c: &Config, message: &str) -> Result<(), ContractError> {
+    if message.trim().is_empty() {
+        return Err(ContractError::CustomError {
+            val: "Message is empty".to_string(),
+        });
+    }
+    let max_len = c.max_message_len.unwrap_or(MAX_LEN_MESSAGE as u64);
+    if message.chars().count() as u64 > max_len {
+        return Err(ContractError::CustomError {
+            val: "Message too long".to_string(),
+        });
+    }
+    if message.chars().any(|ch| ch.is_control() && ch != '\n') {
+        return Err(ContractError::CustomError {
+            val: "Message contains control characters".to_string(),
+        });
     }
 
-    let owner_addr = if let Some(owner) = config.owner.clone() {
-        deps.api.addr_validate(owner.as_str())?
-    } else {
-        info.sender.clone()
+    let lower = message.to_lowercase();
+    for word in lower.split(|ch: char| !ch.is_alphanumeric()) {
+        if !word.is_empty() && BANNED_WORDS.has(storage, word.to_string()) {
+            return Err(ContractError::CustomError {
+                val: "Message contains a banned word".to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// Caps the raw meta size, typed mode also checks the MessageMeta fields
+fn validate_meta(api: &dyn Api, c: &Config, meta: &Option<Binary>) -> Result<(), ContractError> {
+    let meta = match meta {
+        Some(meta) => meta,
+        None => return Ok(()),
     };
+    let max_size = c.max_meta_size.unwrap_or(DEFAULT_MAX_META_SIZE);
+    if meta.len() as u64 > max_size {
+        return Err(ContractError::CustomError {
+            val: "Meta too large".to_string(),
+        });
+    }
+    if !c.typed_meta.unwrap_or(false) {
+        return Ok(());
+    }
 
-    CONFIG.update(deps.storage, |mut state| -> Result<_, ContractError> {
-        if info.sender != state.owner {
-            return Err(ContractError::Unauthorized {});
+    let typed: MessageMeta = from_json(meta).map_err(|_| ContractError::CustomError {
+        val: "Invalid meta".to_string(),
+    })?;
+    for url in [&typed.image, &typed.link]
+        .iter()
+        .filter_map(|u| u.as_ref())
+    {
+        if url.trim().is_empty() || url.chars().count() > MAX_LEN_META_URL {
+            return Err(ContractError::CustomError {
+                val: "Invalid meta url".to_string(),
+            });
         }
-        if let Some(share_fee) = config.share_fee {
-            state.share_fee = share_fee;
+    }
+    if let Some(mentions) = typed.mentions {
+        if mentions.len() > MAX_META_MENTIONS {
+            return Err(ContractError::CustomError {
+                val: "Too many mentions".to_string(),
+            });
         }
-        if let Some(unlock_messages) = config.unlock_messages {
-            state.unlock_messages = Some(unlock_messages);
+        for addr in mentions.iter() {
+            api.addr_validate(addr)?;
         }
-        if let Some(unlock_graffiti) = config.unlock_graffiti {
-            state.unlock_graffiti = Some(unlock_graffiti);
+    }
+    Ok(())
+}
+
+// Collects valid @address mentions from the text & typed meta, invalid ones are plain text
+fn parse_mentions(api: &dyn Api, message: &str, meta: &Option<Binary>) -> Vec<Addr> {
+    let mut candidates: Vec<String> = message
+        .split_whitespace()
+        .filter_map(|word| word.strip_prefix('@'))
+        .map(|word| {
+            word.trim_end_matches(|ch: char| !ch.is_alphanumeric())
+                .to_string()
+        })
+        .collect();
+    if let Some(typed) = meta.as_ref().and_then(|m| from_json::<MessageMeta>(m).ok()) {
+        candidates.extend(typed.mentions.unwrap_or_default());
+    }
+
+    let mut mentions: Vec<Addr> = vec![];
+    for candidate in candidates.iter() {
+        if mentions.len() >= MAX_META_MENTIONS {
+            break;
         }
-        if let Some(unlock_share) = config.unlock_share {
-            state.unlock_share = Some(unlock_share);
+        if let Ok(addr) = api.addr_validate(candidate) {
+            if !mentions.contains(&addr) {
+                mentions.push(addr);
+            }
         }
-        if let Some(rate_decay) = config.rate_decay {
-            state.rate_decay = Some(rate_decay);
+    }
+    mentions
+}
+
+// Same id from either side: sorted participants, plus the NFT when the thread is about one
+// Both sides share one thread per NFT (or a general one with an empty class_id)
+fn conversation_key(a: &Addr, b: &Addr, class_id: &Option<String>) -> (Addr, Addr, String) {
+    let (low, high) = if a < b { (a, b) } else { (b, a) };
+    (
+        low.clone(),
+        high.clone(),
+        class_id.clone().unwrap_or_default(),
+    )
+}
+
+fn notify(storage: &mut dyn Storage, to: &Addr, n: Notification) -> StdResult<()> {
+    let id = NOTIFICATION_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    NOTIFICATION_SEQ.save(storage, &id)?;
+    NOTIFICATIONS.save(
+        storage,
+        (to.clone(), id),
+        &Notification { id: Some(id), ..n },
+    )
+}
+
+// Messages past the report threshold stay hidden until the owner reviews them
+fn is_message_hidden(storage: &dyn Storage, threshold: u64, id: u64) -> StdResult<bool> {
+    let reports = MESSAGE_REPORT_COUNTS
+        .may_load(storage, id)?
+        .unwrap_or_default();
+    Ok(threshold > 0 && reports >= threshold)
+}
+
+// Allow/deny list keys are matched trimmed & lowercased
+fn list_key(key: &str) -> String {
+    key.trim().to_lowercase()
+}
+
+// Denied collections/chains never pass, in allowlist mode they must be explicitly allowed.
+// Once any chain has a status, the chain id is required
+fn is_collection_eligible(
+    storage: &dyn Storage,
+    allowlist_only: bool,
+    contract_addr: &str,
+    chain_id: Option<&str>,
+) -> StdResult<bool> {
+    let collection = COLLECTIONS.may_load(storage, list_key(contract_addr))?;
+    let chain = match chain_id {
+        Some(chain_id) => CHAINS.may_load(storage, list_key(chain_id))?,
+        None => {
+            let chains_listed = CHAINS
+                .keys(storage, None, None, Order::Ascending)
+                .next()
+                .is_some();
+            if chains_listed {
+                return Ok(false);
+            }
+            None
         }
+    };
 
-        // NOTE: Better to do a validated transfer flow, but in this case we're doing naive approach to SHIPPIT
-        if owner_addr != info.sender.clone() && config.owner.is_some() {
-            state.owner = owner_addr;
+    if collection == Some(CollectionStatus::Denied) || chain == Some(CollectionStatus::Denied) {
+        return Ok(false);
+    }
+    if allowlist_only {
+        let chain_ok = chain_id.is_none() || chain == Some(CollectionStatus::Allowed);
+        return Ok(collection == Some(CollectionStatus::Allowed) && chain_ok);
+    }
+    Ok(true)
+}
+
+// When a slot stops being live, a hidden NFT gives up whatever is left of its slot
+fn slot_end(storage: &dyn Storage, cur: &ScheduledNft, slot: u64, now: u64) -> StdResult<u64> {
+    let end = cur.start.saturating_add(slot);
+    if load_visible_nft(storage, cur.class_id.clone())?.is_none() {
+        return Ok(end.min(now));
+    }
+    Ok(end)
+}
+
+// A pending NFT starts once the previous slot ends, but never before it was queued
+fn next_slot_start(prev_end: Option<u64>, queued_ts: u64) -> u64 {
+    match prev_end {
+        Some(end) => end.max(queued_ts),
+        None => queued_ts,
+    }
+}
+
+// Pops expired slots off the pending queue, so SCHEDULE_CURRENT is the one live at `now`
+fn advance_schedule(storage: &mut dyn Storage, now: u64, slot: u64) -> StdResult<()> {
+    let mut current = SCHEDULE_CURRENT.may_load(storage)?;
+    loop {
+        let prev_end = match &current {
+            Some(cur) => Some(slot_end(storage, cur, slot, now)?),
+            None => None,
+        };
+        if prev_end.is_some_and(|end| end > now) {
+            break;
         }
+        let (key, pending) = match SCHEDULE_PENDING.first(storage)? {
+            Some(next) => next,
+            None => break,
+        };
+        SCHEDULE_PENDING.remove(storage, key);
+        if load_visible_nft(storage, pending.class_id.clone())?.is_none() {
+            continue;
+        }
+        current = Some(ScheduledNft {
+            start: next_slot_start(prev_end, pending.ts),
+            class_id: pending.class_id,
+        });
+    }
+    if let Some(cur) = current {
+        SCHEDULE_CURRENT.save(storage, &cur)?;
+    }
+    Ok(())
+}
 
-        Ok(state)
-    })?;
+// Read-only version of advance_schedule, returns the live slot (if any) then upcoming ones
+fn schedule_view(
+    storage: &dyn Storage,
+    now: u64,
+    slot: u64,
+    limit: usize,
+) -> StdResult<Vec<ScheduledNft>> {
+    let mut current = SCHEDULE_CURRENT.may_load(storage)?;
+    let mut upcoming: Vec<ScheduledNft> = vec![];
+
+    for res in SCHEDULE_PENDING.range(storage, None, None, Order::Ascending) {
+        if upcoming.len() >= limit {
+            break;
+        }
+        let (_, pending) = res?;
+        if load_visible_nft(storage, pending.class_id.clone())?.is_none() {
+            continue;
+        }
+        let prev_end = match (upcoming.last(), &current) {
+            (Some(prev), _) => Some(prev.start.saturating_add(slot)),
+            (None, Some(cur)) => Some(slot_end(storage, cur, slot, now)?),
+            (None, None) => None,
+        };
+        let next = ScheduledNft {
+            start: next_slot_start(prev_end, pending.ts),
+            class_id: pending.class_id,
+        };
+        if next.start <= now {
+            // already took over the live slot
+            current = Some(next);
+        } else {
+            upcoming.push(next);
+        }
+    }
 
-    Ok(Response::new().add_attribute("method", "change_config"))
+    // an expired or hidden slot with nothing queued behind it is not live anymore
+    let mut schedule: Vec<ScheduledNft> = vec![];
+    if let Some(cur) = current {
+        if slot_end(storage, &cur, slot, now)? > now {
+            schedule.push(cur);
+        }
+    }
+    schedule.extend(upcoming);
+    schedule.truncate(limit);
+    Ok(schedule)
 }
 
-pub fn rate(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    class_id: String,
-    v: u8,
-) -> Result<Response, ContractError> {
-    // check NFT exists
-    if !NFTS.has(deps.storage, class_id.clone()) {
+// Start & end of the boost window holding `ts`, windows are aligned to block time
+fn boost_window(c: &Config, ts: u64) -> (u64, u64) {
+    let duration = c.boost_duration.unwrap_or(DEFAULT_BOOST_DURATION).max(1);
+    let start = ts - ts % duration;
+    (start, start + duration)
+}
+
+// Bids for windows that haven't started yet, their funds are still refundable
+fn escrowed_bids(storage: &dyn Storage, now: u64) -> StdResult<Vec<(u64, BoostBid)>> {
+    BOOST_BIDS
+        .range(storage, Some(Bound::exclusive(now)), None, Order::Ascending)
+        .collect()
+}
+
+fn validate_collection_name(name: &str) -> Result<(), ContractError> {
+    if name.trim().is_empty() || name.chars().count() > MAX_LEN_COLLECTION_NAME {
         return Err(ContractError::CustomError {
-            val: "NFT doesnt exist, cannot rate nothing silly human".to_string(),
+            val: "Invalid collection name".to_string(),
         });
     }
+    Ok(())
+}
 
-    // Check if already rated!
-    let rate_key = (class_id.clone(), info.sender.clone());
-    if NFT_RATINGS.has(deps.storage, rate_key.clone()) {
+fn check_collection_limit(storage: &dyn Storage, addr: &Addr) -> Result<(), ContractError> {
+    let total = SAVED_COLLECTIONS
+        .prefix(addr.clone())
+        .keys(storage, None, None, Order::Ascending)
+        .count();
+    if total >= MAX_SAVED_COLLECTIONS {
         return Err(ContractError::CustomError {
-            val: "Already rated this nft, cannot rate again".to_string(),
+            val: "Maximum collections reached".to_string(),
         });
     }
+    Ok(())
+}
 
-    // check value is valid (thank you GPT!! LOL)
-    if !(1..=5).contains(&v) {
+fn check_collection_size(sc: &SavedCollection) -> Result<(), ContractError> {
+    if sc.count >= MAX_SAVED_PER_COLLECTION {
         return Err(ContractError::CustomError {
-            val: "Invalid rate value".to_string(),
+            val: "Collection is full".to_string(),
         });
     }
+    Ok(())
+}
 
-    let ts = env.block.time.seconds();
-    let sender_rate = Rate { ts, v };
-    NFT_RATINGS.save(deps.storage, rate_key, &sender_rate)?;
-
-    // get the previous ts offset via remainders
-    let day_remainder = ts % DAY_IN_SECONDS;
-    let year_remainder = ts % (DAY_IN_SECONDS * 366);
-    let last_day_ts = ts.saturating_sub(day_remainder);
-    let last_year_ts = ts.saturating_sub(year_remainder);
+// Only migrated flat lists can be over the cap, those need unsaving first
+fn check_collection_rewrite(sc: &SavedCollection) -> Result<(), ContractError> {
+    if sc.count > MAX_SAVED_PER_COLLECTION {
+        return Err(ContractError::CustomError {
+            val: "Collection too large, unsave some NFTs first".to_string(),
+        });
+    }
+    Ok(())
+}
 
-    // Truncate day caches older than 1 year ago
-    let ath_keys = NFT_RATE_DAY_ATH
-        .keys(
-            deps.storage,
-            None,
-            Some(Bound::inclusive(last_year_ts)),
-            Order::Descending,
-        )
-        .map(|res| res.unwrap())
-        .collect::<Vec<_>>();
-    let atl_keys = NFT_RATE_DAY_ATL
-        .keys(
-            deps.storage,
-            None,
-            Some(Bound::inclusive(last_year_ts)),
-            Order::Descending,
-        )
-        .map(|res| res.unwrap())
-        .collect::<Vec<_>>();
+// Named collections must be created first, the default one is created on demand
+fn load_saved_collection(
+    storage: &dyn Storage,
+    addr: &Addr,
+    name: &str,
+    ts: u64,
+) -> Result<SavedCollection, ContractError> {
+    match SAVED_COLLECTIONS.may_load(storage, (addr.clone(), name.to_string()))? {
+        Some(sc) => Ok(sc),
+        None if name == DEFAULT_SAVED_COLLECTION => {
+            check_collection_limit(storage, addr)?;
+            Ok(SavedCollection {
+                name: name.to_string(),
+                ts,
+                count: 0,
+            })
+        }
+        None => Err(ContractError::CustomError {
+            val: "Collection doesnt exist".to_string(),
+        }),
+    }
+}
 
-    for hk in ath_keys.into_iter() {
-        NFT_RATE_DAY_ATH.remove(deps.storage, hk);
+// Keeps the "most saved" list truncated, same approach as NFT_RATE_ATH
+fn update_most_saved(storage: &mut dyn Storage, class_id: String, count: u64) -> StdResult<()> {
+    if count == 0 {
+        NFT_SAVE_TOP.remove(storage, class_id);
+        return Ok(());
     }
-    for lk in atl_keys.into_iter() {
-        NFT_RATE_DAY_ATL.remove(deps.storage, lk);
+    NFT_SAVE_TOP.save(storage, class_id, &count)?;
+
+    let mut all_top = NFT_SAVE_TOP
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, u64)>>>()?;
+    if all_top.len() > MAX_LEN_ALL_TIME {
+        all_top.sort_by_key(|(_, saves)| std::cmp::Reverse(*saves));
+        for (k, _) in all_top[MAX_LEN_ALL_TIME..].iter() {
+            NFT_SAVE_TOP.remove(storage, k.to_string());
+        }
     }
+    Ok(())
+}
 
-    // compute total rate counts
-    let rate_counts = NFT_RATE_COUNTS.update(
-        deps.storage,
+// Counts a user as a saver of an NFT, only the first time they save it in any collection
+fn add_saver(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    class_id: String,
+    ts: u64,
+) -> Result<(), ContractError> {
+    if NFT_SAVERS.has(storage, (class_id.clone(), addr.clone())) {
+        return Ok(());
+    }
+    NFT_SAVERS.save(storage, (class_id.clone(), addr.clone()), &ts)?;
+    USER_SAVED_NFTS.save(storage, (addr.clone(), class_id.clone()), &ts)?;
+    bump_saves_received(storage, addr, class_id.clone(), ts, true)?;
+    let count = NFT_SAVE_COUNTS.update(
+        storage,
         class_id.clone(),
-        |counts| -> Result<_, ContractError> {
-            match counts {
-                Some(rc) => {
-                    let mut r = rc;
-                    // update all counts
-                    r.all.ts = 0;
-                    r.all.total = r.all.total.saturating_add(1);
-                    r.all.sum = r.all.sum.saturating_add(v as u64);
+        |count| -> Result<_, ContractError> { Ok(count.unwrap_or_default().saturating_add(1)) },
+    )?;
+    update_most_saved(storage, class_id, count)?;
 
-                    if r.day.ts + last_day_ts > ts {
-                        // Still within range
-                        r.day.ts = ts;
-                        r.day.total = r.day.total.saturating_add(1);
-                        r.day.sum = r.day.sum.saturating_add(v as u64);
-                    } else {
-                        // outside range, start fresh
-                        r.day.ts = last_day_ts;
-                        r.day.total = 1;
-                        r.day.sum = v as u64;
-                    }
-                    Ok(r)
-                }
-                None => {
-                    let default_rc = RateCount {
-                        ts,
-                        sum: v as u64,
-                        total: 1,
-                    };
-                    Ok(RateCounts {
-                        all: default_rc.clone(),
-                        day: default_rc,
-                    })
+    // update user stats
+    USER_STATS.update(storage, addr.clone(), |stats| -> Result<_, ContractError> {
+        match stats {
+            Some(s) => {
+                let mut st = s;
+                st.saves = st.saves.saturating_add(1);
+                Ok(st)
+            }
+            None => Ok(UserStats {
+                last_rate_ts: ts,
+                ratings: 0,
+                saves: 1,
+                shares: 0,
+                ..UserStats::default()
+            }),
+        }
+    })?;
+
+    // update total stats
+    STATS.update(storage, |mut stats| -> Result<_, ContractError> {
+        stats.saves = stats.saves.saturating_add(1);
+        Ok(stats)
+    })?;
+    Ok(())
+}
+
+// Drops a user as a saver once the NFT is gone from all of their collections
+fn remove_saver(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    class_id: String,
+) -> Result<(), ContractError> {
+    let saved_ts = match NFT_SAVERS.may_load(storage, (class_id.clone(), addr.clone()))? {
+        Some(ts) => ts,
+        None => return Ok(()),
+    };
+    let collections = SAVED_COLLECTIONS
+        .prefix(addr.clone())
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+    for name in collections.into_iter() {
+        if SAVED_NFTS.has(storage, (addr.clone(), name, class_id.clone())) {
+            return Ok(());
+        }
+    }
+
+    NFT_SAVERS.remove(storage, (class_id.clone(), addr.clone()));
+    USER_SAVED_NFTS.remove(storage, (addr.clone(), class_id.clone()));
+    bump_saves_received(storage, addr, class_id.clone(), saved_ts, false)?;
+    let count = NFT_SAVE_COUNTS
+        .may_load(storage, class_id.clone())?
+        .unwrap_or_default()
+        .saturating_sub(1);
+    if count > 0 {
+        NFT_SAVE_COUNTS.save(storage, class_id.clone(), &count)?;
+    } else {
+        NFT_SAVE_COUNTS.remove(storage, class_id.clone());
+    }
+    if NFT_SAVE_TOP.has(storage, class_id.clone()) {
+        update_most_saved(storage, class_id, count)?;
+    }
+
+    if let Some(mut st) = USER_STATS.may_load(storage, addr.clone())? {
+        st.saves = st.saves.saturating_sub(1);
+        USER_STATS.save(storage, addr.clone(), &st)?;
+    }
+    STATS.update(storage, |mut stats| -> Result<_, ContractError> {
+        stats.saves = stats.saves.saturating_sub(1);
+        Ok(stats)
+    })?;
+    Ok(())
+}
+
+// Honors the owner's display preference for the claimed viewer, it's a UI hint and not access control
+// The owner can always see their own saves, FollowersOnly needs the viewer to follow them
+fn can_view_saved(storage: &dyn Storage, addr: &Addr, viewer: Option<&Addr>) -> StdResult<bool> {
+    if viewer == Some(addr) {
+        return Ok(true);
+    }
+    let visibility = SAVED_VISIBILITY
+        .may_load(storage, addr.clone())?
+        .unwrap_or(Visibility::Public);
+    match (visibility, viewer) {
+        (Visibility::Public, _) => Ok(true),
+        (Visibility::FollowersOnly, Some(viewer)) => {
+            Ok(FOLLOWING.has(storage, (viewer.clone(), addr.clone())))
+        }
+        _ => Ok(false),
+    }
+}
+
+// Either enough ratings, or enough reputation when that gate is configured
+fn has_unlocked(stats: &UserStats, ratings: u64, reputation: Option<u64>) -> bool {
+    stats.ratings >= ratings
+        || reputation
+            .map(|r| stats.reputation.unwrap_or_default() >= r)
+            .unwrap_or(false)
+}
+
+fn add_reputation(storage: &mut dyn Storage, addr: &Addr, points: u64) -> StdResult<()> {
+    if let Some(mut st) = USER_STATS.may_load(storage, addr.clone())? {
+        st.reputation = Some(st.reputation.unwrap_or_default().saturating_add(points));
+        USER_STATS.save(storage, addr.clone(), &st)?;
+    }
+    Ok(())
+}
+
+// Scores early raters by distance from the average (in hundredths of a star), and the sharer
+// Returns badge events for anyone the new reputation unlocked something for
+fn settle_reputation(
+    storage: &mut dyn Storage,
+    c: &Config,
+    class_id: String,
+    all: &RateCount,
+    ts: u64,
+) -> StdResult<Vec<Event>> {
+    REPUTATION_SETTLED.save(storage, class_id.clone(), &ts)?;
+    let avg = all.sum.saturating_mul(100) / all.total.max(1);
+
+    let early = EARLY_RATINGS
+        .prefix(class_id.clone())
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Addr, u8)>>>()?;
+    let mut credited: Vec<Addr> = vec![];
+    for (addr, v) in early.into_iter() {
+        let diff = (u64::from(v) * 100).abs_diff(avg);
+        let points = if diff <= 50 {
+            REPUTATION_ACCURATE
+        } else if diff <= 100 {
+            REPUTATION_CLOSE
+        } else {
+            0
+        };
+        EARLY_RATINGS.remove(storage, (class_id.clone(), addr.clone()));
+        if points > 0 {
+            add_reputation(storage, &addr, points)?;
+            credited.push(addr);
+        }
+    }
+
+    if avg >= 400 {
+        if let Some(sharer) = NFTS.may_load(storage, class_id)?.and_then(|n| n.sharer) {
+            add_reputation(storage, &sharer, REPUTATION_SHARE_BONUS)?;
+            credited.push(sharer);
+        }
+    }
+
+    let mut events = vec![];
+    for addr in credited.into_iter() {
+        if let Some(st) = USER_STATS.may_load(storage, addr.clone())? {
+            events.extend(check_milestones(storage, c, &addr, &st, ts)?);
+        }
+    }
+    Ok(events)
+}
+
+// Rating again the calendar day after the last active one keeps the streak going
+fn update_streak(mut st: UserStats, day_ts: u64) -> UserStats {
+    match st.last_active_day {
+        Some(last) if last == day_ts => return st,
+        Some(last) if last.saturating_add(CALENDAR_DAY_IN_SECONDS) == day_ts => {
+            st.current_streak = Some(st.current_streak.unwrap_or_default().saturating_add(1));
+        }
+        _ => st.current_streak = Some(1),
+    }
+    st.last_active_day = Some(day_ts);
+    st.active_days = Some(st.active_days.unwrap_or_default().saturating_add(1));
+    st.longest_streak = st.longest_streak.max(st.current_streak);
+    st
+}
+
+// A streak is only current while the user rated today or yesterday
+fn live_streak(st: &UserStats, now: u64) -> u64 {
+    let today = now.saturating_sub(now % CALENDAR_DAY_IN_SECONDS);
+    match st.last_active_day {
+        Some(last) if last.saturating_add(CALENDAR_DAY_IN_SECONDS) >= today => {
+            st.current_streak.unwrap_or_default()
+        }
+        _ => 0,
+    }
+}
+
+// Moves a user within a ranked metric, zero scores are left out
+fn set_user_score(
+    storage: &mut dyn Storage,
+    metric: &str,
+    addr: &Addr,
+    score: u64,
+) -> StdResult<()> {
+    let score_key = (metric.to_string(), addr.clone());
+    if let Some(prev) = USER_SCORES.may_load(storage, score_key.clone())? {
+        if prev == score {
+            return Ok(());
+        }
+        USER_RANKS.remove(storage, (metric.to_string(), prev, addr.clone()));
+    }
+    if score == 0 {
+        USER_SCORES.remove(storage, score_key);
+        return Ok(());
+    }
+    USER_SCORES.save(storage, score_key, &score)?;
+    USER_RANKS.save(
+        storage,
+        (metric.to_string(), score, addr.clone()),
+        &Empty {},
+    )
+}
+
+// Current streaks are ranked per last active day, so only today's & yesterday's boards are live
+fn streak_board_key(day_ts: u64) -> String {
+    format!("{}:{}", UserMetric::CurrentStreak.as_str(), day_ts)
+}
+
+// Window a ts falls in as ("d" or "m", window start), all time has none
+fn leaderboard_window(period: &LeaderboardPeriod, ts: u64) -> Option<(&'static str, u64)> {
+    match period {
+        LeaderboardPeriod::AllTime => None,
+        LeaderboardPeriod::Day => Some(("d", ts.saturating_sub(ts % CALENDAR_DAY_IN_SECONDS))),
+        LeaderboardPeriod::Month => {
+            Some(("m", ts.saturating_sub(ts % LEADERBOARD_MONTH_IN_SECONDS)))
+        }
+    }
+}
+
+fn leaderboard_key(metric: &UserMetric, period: &LeaderboardPeriod, ts: u64) -> String {
+    match leaderboard_window(period, ts) {
+        Some((window, start)) => format!("{}:{}:{}", metric.as_str(), window, start),
+        None => metric.as_str().to_string(),
+    }
+}
+
+// Clears a few rows from the oldest window before `current`, dropping it once empty
+fn prune_board_window(storage: &mut dyn Storage, board: String, current: u64) -> StdResult<()> {
+    let expired = USER_BOARD_WINDOWS
+        .prefix(board.clone())
+        .keys(
+            storage,
+            None,
+            Some(Bound::exclusive(current)),
+            Order::Ascending,
+        )
+        .next()
+        .transpose()?;
+    if let Some(start) = expired {
+        let key = format!("{}:{}", board, start);
+        let rows = USER_RANKS
+            .sub_prefix(key.clone())
+            .keys(storage, None, None, Order::Ascending)
+            .take(BOARD_PRUNE_LIMIT)
+            .collect::<StdResult<Vec<(u64, Addr)>>>()?;
+        if rows.len() < BOARD_PRUNE_LIMIT {
+            USER_BOARD_WINDOWS.remove(storage, (board, start));
+        }
+        for (score, addr) in rows.into_iter() {
+            USER_RANKS.remove(storage, (key.clone(), score, addr.clone()));
+            USER_SCORES.remove(storage, (key.clone(), addr));
+        }
+    }
+    Ok(())
+}
+
+// Counts an event in the all time & windowed boards, undo with the original event ts
+fn bump_user_score(
+    storage: &mut dyn Storage,
+    metric: UserMetric,
+    addr: &Addr,
+    ts: u64,
+    add: bool,
+) -> StdResult<()> {
+    for period in [
+        LeaderboardPeriod::AllTime,
+        LeaderboardPeriod::Day,
+        LeaderboardPeriod::Month,
+    ] {
+        let key = leaderboard_key(&metric, &period, ts);
+        let prev = USER_SCORES
+            .may_load(storage, (key.clone(), addr.clone()))?
+            .unwrap_or_default();
+        let next = if add {
+            prev.saturating_add(1)
+        } else {
+            prev.saturating_sub(1)
+        };
+        set_user_score(storage, &key, addr, next)?;
+
+        if let Some((window, start)) = leaderboard_window(&period, ts) {
+            let board = format!("{}:{}", metric.as_str(), window);
+            if next > 0 && !USER_BOARD_WINDOWS.has(storage, (board.clone(), start)) {
+                USER_BOARD_WINDOWS.save(storage, (board.clone(), start), &Empty {})?;
+            }
+            prune_board_window(storage, board, start)?;
+        }
+    }
+    Ok(())
+}
+
+// Credits the sharer of an NFT when someone else saves it
+fn bump_saves_received(
+    storage: &mut dyn Storage,
+    saver: &Addr,
+    class_id: String,
+    ts: u64,
+    add: bool,
+) -> StdResult<()> {
+    if let Some(sharer) = NFTS.may_load(storage, class_id)?.and_then(|n| n.sharer) {
+        if sharer != *saver {
+            bump_user_score(storage, UserMetric::SavesReceived, &sharer, ts, add)?;
+        }
+    }
+    Ok(())
+}
+
+// Records a badge once, returning the event to emit when it is new
+fn award_badge(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    kind: BadgeKind,
+    ts: u64,
+) -> StdResult<Option<Event>> {
+    let key = (addr.clone(), kind.as_str().to_string());
+    if USER_BADGES.has(storage, key.clone()) {
+        return Ok(None);
+    }
+    let event = Event::new("badge")
+        .add_attribute("addr", addr.to_string())
+        .add_attribute("badge", kind.as_str());
+    USER_BADGES.save(storage, key, &Badge { kind, ts })?;
+    Ok(Some(event))
+}
+
+fn unlock_progress(c: &Config, stats: &UserStats) -> Vec<UnlockProgress> {
+    [
+        (Unlock::Messages, c.unlock_messages, c.rep_unlock_messages),
+        (Unlock::Graffiti, c.unlock_graffiti, c.rep_unlock_graffiti),
+        (Unlock::Share, c.unlock_share, c.rep_unlock_share),
+        (
+            Unlock::DirectMessages,
+            c.unlock_dms.or(Some(DEFAULT_UNLOCK_DMS)),
+            None,
+        ),
+    ]
+    .iter()
+    .map(|(unlock, ratings, reputation)| {
+        let ratings_required = ratings.unwrap_or(5);
+        UnlockProgress {
+            unlock: unlock.clone(),
+            ratings: stats.ratings,
+            ratings_required,
+            reputation_required: *reputation,
+            unlocked: has_unlocked(stats, ratings_required, *reputation),
+        }
+    })
+    .collect()
+}
+
+// Awards any milestone badges the user has reached, safe to call repeatedly
+fn check_milestones(
+    storage: &mut dyn Storage,
+    c: &Config,
+    addr: &Addr,
+    stats: &UserStats,
+    ts: u64,
+) -> StdResult<Vec<Event>> {
+    let mut earned = vec![];
+    if stats.ratings >= 1 {
+        earned.push(BadgeKind::FirstRating);
+    }
+    if stats.ratings >= BADGE_RATINGS_MILESTONE {
+        earned.push(BadgeKind::HundredRatings);
+    }
+    if stats.current_streak.unwrap_or_default() >= BADGE_STREAK_DAYS {
+        earned.push(BadgeKind::RatingStreak);
+    }
+    for progress in unlock_progress(c, stats).into_iter() {
+        if !progress.unlocked {
+            continue;
+        }
+        match progress.unlock {
+            Unlock::Messages => earned.push(BadgeKind::MessagesUnlocked),
+            Unlock::Share => earned.push(BadgeKind::ShareUnlocked),
+            Unlock::DirectMessages => earned.push(BadgeKind::DirectMessagesUnlocked),
+            Unlock::Graffiti => earned.push(BadgeKind::GraffitiUnlocked),
+        }
+    }
+
+    let mut events = vec![];
+    for kind in earned.into_iter() {
+        events.extend(award_badge(storage, addr, kind, ts)?);
+    }
+    Ok(events)
+}
+
+// ASCII letters, numbers, "_" & "-" only, so names stay readable in mentions & URLs
+// and look-alike letters from other scripts can't impersonate a taken name
+fn validate_display_name(name: &str) -> Result<(), ContractError> {
+    let len = name.chars().count();
+    if !(MIN_LEN_DISPLAY_NAME..=MAX_LEN_DISPLAY_NAME).contains(&len)
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-')
+    {
+        return Err(ContractError::CustomError {
+            val: "Invalid display name".to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn with_profiles(
+    deps: Deps,
+    msgs: Vec<MessageResponse>,
+    include_profiles: Option<bool>,
+) -> StdResult<Vec<MessageResponse>> {
+    if !include_profiles.unwrap_or(false) {
+        return Ok(msgs);
+    }
+    msgs.into_iter()
+        .map(|res| {
+            let author = PROFILES.may_load(deps.storage, res.message.from.clone())?;
+            Ok(MessageResponse { author, ..res })
+        })
+        .collect()
+}
+
+fn record_activity(storage: &mut dyn Storage, addr: &Addr, activity: Activity) -> StdResult<()> {
+    let id = ACTIVITY_SEQ.may_load(storage)?.unwrap_or_default() + 1;
+    ACTIVITY_SEQ.save(storage, &id)?;
+    USER_ACTIVITY.save(
+        storage,
+        (addr.clone(), id),
+        &Activity {
+            id: Some(id),
+            ..activity
+        },
+    )
+}
+
+// Skips activity whose NFT, rating or message has since been taken down
+fn is_activity_live(storage: &dyn Storage, activity: &Activity) -> StdResult<bool> {
+    if load_visible_nft(storage, activity.class_id.clone())?.is_none() {
+        return Ok(false);
+    }
+    match activity.kind {
+        ActivityKind::Share => Ok(true),
+        // a later re-rating replaces this one
+        ActivityKind::Rate => Ok(NFT_RATINGS
+            .may_load(storage, (activity.class_id.clone(), activity.from.clone()))?
+            .map(|rate| rate.ts == activity.ts && Some(rate.v) == activity.v)
+            .unwrap_or(false)),
+        ActivityKind::Message => Ok(activity
+            .msg_id
+            .map(|id| MESSAGES.has(storage, id))
+            .unwrap_or(false)),
+    }
+}
+
+// Rows are only kept while either count is non-zero
+fn update_follow_counts(
+    storage: &mut dyn Storage,
+    follower: &Addr,
+    followed: &Addr,
+    add: bool,
+) -> StdResult<()> {
+    let step = |count: u64| -> u64 {
+        if add {
+            count.saturating_add(1)
+        } else {
+            count.saturating_sub(1)
+        }
+    };
+
+    for (addr, is_follower) in [(follower, true), (followed, false)] {
+        let mut counts = FOLLOW_COUNTS
+            .may_load(storage, addr.clone())?
+            .unwrap_or_default();
+        if is_follower {
+            counts.following = step(counts.following);
+        } else {
+            counts.followers = step(counts.followers);
+        }
+        if counts == FollowCounts::default() {
+            FOLLOW_COUNTS.remove(storage, addr.clone());
+        } else {
+            FOLLOW_COUNTS.save(storage, addr.clone(), &counts)?;
+        }
+    }
+    Ok(())
+}
+
+fn assert_can_view_saved(
+    storage: &dyn Storage,
+    addr: &Addr,
+    viewer: Option<&Addr>,
+) -> StdResult<()> {
+    if !can_view_saved(storage, addr, viewer)? {
+        return Err(StdError::generic_err("Saved list not shown to this viewer"));
+    }
+    Ok(())
+}
+
+fn is_visible(nft: &Nft) -> bool {
+    !nft.hidden.unwrap_or(false)
+}
+
+// Loads an NFT only if it exists and hasnt been taken down
+fn load_visible_nft(storage: &dyn Storage, class_id: String) -> StdResult<Option<Nft>> {
+    let nft = NFTS.may_load(storage, class_id)?;
+    Ok(nft.filter(is_visible))
+}
+
+// Scans up to `limit` messages then ratings from the purge cursor, removing the ones by addr.
+// Returns (purged messages, purged ratings, done)
+fn purge_batch(
+    storage: &mut dyn Storage,
+    addr: &Addr,
+    limit: usize,
+) -> Result<(u64, u64, bool), ContractError> {
+    let mut cursor = PURGES.may_load(storage, addr.clone())?.unwrap_or_default();
+    let mut purged_messages: u64 = 0;
+    let mut purged_ratings: u64 = 0;
+
+    let mut scanned: usize = 0;
+    if !cursor.messages_done {
+        let start = cursor.messages_after.map(Bound::exclusive);
+        let msgs = MESSAGES
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<(u64, Message)>>>()?;
+        scanned = msgs.len();
+        cursor.messages_done = msgs.len() < limit;
+        for (id, msg) in msgs.into_iter() {
+            cursor.messages_after = Some(id);
+            if msg.from == *addr {
+                delete_message(storage, id)?;
+                purged_messages += 1;
+            }
+        }
+    }
+
+    let mut done = false;
+    if cursor.messages_done && scanned < limit {
+        let remaining = limit - scanned;
+        let start = cursor.ratings_after.clone().map(Bound::exclusive);
+        let rates = NFT_RATINGS
+            .range(storage, start, None, Order::Ascending)
+            .take(remaining)
+            .collect::<StdResult<Vec<((String, Addr), Rate)>>>()?;
+        done = rates.len() < remaining;
+        for ((class_id, a), r) in rates.into_iter() {
+            cursor.ratings_after = Some((class_id.clone(), a.clone()));
+            if a == *addr {
+                remove_rating(storage, class_id, a, r)?;
+                purged_ratings += 1;
+            }
+        }
+    }
+
+    if done {
+        PURGES.remove(storage, addr.clone());
+    } else {
+        PURGES.save(storage, addr.clone(), &cursor)?;
+    }
+    if purged_ratings > 0 {
+        if let Some(mut st) = USER_STATS.may_load(storage, addr.clone())? {
+            st.ratings = st.ratings.saturating_sub(purged_ratings);
+            USER_STATS.save(storage, addr.clone(), &st)?;
+        }
+    }
+    if purged_messages > 0 {
+        STATS.update(storage, |mut stats| -> Result<_, ContractError> {
+            stats.messages = stats.messages.saturating_sub(purged_messages);
+            Ok(stats)
+        })?;
+    }
+
+    Ok((purged_messages, purged_ratings, done))
+}
+
+// Undo a single rating, pulling its value back out of all the counts & lists it touched
+fn remove_rating(
+    storage: &mut dyn Storage,
+    class_id: String,
+    addr: Addr,
+    rate: Rate,
+) -> Result<(), ContractError> {
+    NFT_RATINGS.remove(storage, (class_id.clone(), addr.clone()));
+    EARLY_RATINGS.remove(storage, (class_id.clone(), addr.clone()));
+    bump_user_score(storage, UserMetric::Ratings, &addr, rate.ts, false)?;
+    let v = rate.v as u64;
+
+    if let Some(mut rc) = NFT_RATE_COUNTS.may_load(storage, class_id.clone())? {
+        rc.all.total = rc.all.total.saturating_sub(1);
+        rc.all.sum = rc.all.sum.saturating_sub(v);
+        if rate.ts >= rc.day.ts {
+            rc.day.total = rc.day.total.saturating_sub(1);
+            rc.day.sum = rc.day.sum.saturating_sub(v);
+        }
+
+        // ranked lists only keep non-empty counts
+        for list in [NFT_RATE_ATH, NFT_RATE_ATL] {
+            if list.has(storage, class_id.clone()) {
+                if rc.all.total > 0 {
+                    list.save(storage, class_id.clone(), &rc.all)?;
+                } else {
+                    list.remove(storage, class_id.clone());
+                }
+            }
+        }
+        NFT_RATE_COUNTS.save(storage, class_id.clone(), &rc)?;
+    }
+
+    // day buckets
+    let day_ts = rate.ts.saturating_sub(rate.ts % DAY_IN_SECONDS);
+    for bucket in [NFT_RATE_DAY_ATH, NFT_RATE_DAY_ATL] {
+        if let Some(mut days) = bucket.may_load(storage, day_ts)? {
+            if let Some(day) = days.get_mut(&class_id) {
+                day.total = day.total.saturating_sub(1);
+                day.sum = day.sum.saturating_sub(v);
+                if day.total == 0 {
+                    days.remove(&class_id);
                 }
+                bucket.save(storage, day_ts, &days)?;
+            }
+        }
+    }
+
+    STATS.update(storage, |mut stats| -> Result<_, ContractError> {
+        stats.ratings = stats.ratings.saturating_sub(1);
+        Ok(stats)
+    })?;
+
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let state = Config {
+        owner: info.sender,
+        share_fee: msg.share_fee.clone(),
+        save_fee: msg.save_fee,
+
+        unlock_messages: Some(DEFAULT_UNLOCK_MESSAGES),
+        unlock_graffiti: Some(DEFAULT_UNLOCK_GRAFFITI),
+        unlock_share: Some(DEFAULT_UNLOCK_SHARES),
+        unlock_dms: Some(DEFAULT_UNLOCK_DMS),
+        rep_unlock_messages: None,
+        rep_unlock_graffiti: None,
+        rep_unlock_share: None,
+        max_shares: Some(DEFAULT_USER_MAX_SHARES),
+        rate_decay: Some(DEFAULT_RATE_DECAY),
+        report_threshold: Some(DEFAULT_REPORT_THRESHOLD),
+        allowlist_only: Some(false),
+        slot_duration: Some(DEFAULT_SLOT_DURATION),
+        schedule_priority: Some(SchedulePriority::Fifo),
+        boost_min_bid: None,
+        boost_duration: Some(DEFAULT_BOOST_DURATION),
+        message_edit_window: Some(DEFAULT_MESSAGE_EDIT_WINDOW),
+        max_message_len: Some(MAX_LEN_MESSAGE as u64),
+        max_meta_size: Some(DEFAULT_MAX_META_SIZE),
+        typed_meta: Some(false),
+        reactions: Some(DEFAULT_REACTIONS.iter().map(|r| r.to_string()).collect()),
+    };
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    CONFIG.save(deps.storage, &state)?;
+
+    let stats = TotalStats {
+        nfts: 0,
+        ratings: 0,
+        messages: 0,
+        saves: 0,
+    };
+    STATS.save(deps.storage, &stats)?;
+
+    Ok(Response::new().add_attribute("method", "instantiate"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let prev = get_contract_version(deps.storage)?;
+    if prev.contract != CONTRACT_NAME {
+        return Err(ContractError::CustomError {
+            val: format!("Cannot migrate from {}", prev.contract),
+        });
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Everything below is one time, only state from before the user boards needs it
+    if parse_version(&prev.version) >= parse_version(BOARDS_VERSION) {
+        return Ok(Response::new()
+            .add_attribute("method", "migrate")
+            .add_attribute("from_version", prev.version));
+    }
+
+    // Nft.index used to be stored 1-based, rewrite to match the 0-based LIST positions
+    let class_ids = LIST
+        .iter(deps.storage)?
+        .collect::<StdResult<Vec<String>>>()?;
+    let mut updated: u64 = 0;
+    for (i, class_id) in class_ids.into_iter().enumerate() {
+        if let Some(mut nft) = NFTS.may_load(deps.storage, class_id.clone())? {
+            let index = Some(i as u64);
+            if nft.index != index {
+                nft.index = index;
+                NFTS.save(deps.storage, class_id, &nft)?;
+                updated += 1;
             }
+        }
+    }
+
+    // Flat saved lists move into each user's default collection
+    let legacy_saved = USER_SAVED
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Addr, Vec<String>)>>>()?;
+    let ts = env.block.time.seconds();
+    for (addr, class_ids) in legacy_saved.into_iter() {
+        let name = DEFAULT_SAVED_COLLECTION.to_string();
+        let mut sc = SAVED_COLLECTIONS
+            .may_load(deps.storage, (addr.clone(), name.clone()))?
+            .unwrap_or(SavedCollection {
+                name: name.clone(),
+                ts,
+                count: 0,
+            });
+        for class_id in class_ids.into_iter() {
+            let key = (addr.clone(), name.clone(), class_id);
+            if !SAVED_NFTS.has(deps.storage, key.clone()) {
+                SAVED_NFTS.save(deps.storage, key, &SavedMeta { ts, note: None })?;
+                sc.count = sc.count.saturating_add(1);
+            }
+        }
+        SAVED_COLLECTIONS.save(deps.storage, (addr.clone(), name), &sc)?;
+        USER_SAVED.remove(deps.storage, addr);
+    }
+
+    // Per-NFT message id lists move into composite keys
+    let legacy_msg_ids = MESSAGES_IDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, Vec<u64>)>>>()?;
+    for (class_id, ids) in legacy_msg_ids.into_iter() {
+        for id in ids.into_iter() {
+            if MESSAGES.has(deps.storage, id) {
+                NFT_MESSAGES.save(deps.storage, (class_id.clone(), id), &Empty {})?;
+            }
+        }
+        MESSAGES_IDS.remove(deps.storage, class_id);
+    }
+
+    // Rebuild per-NFT savers, user & total save counts used to include duplicate saves
+    let saved = SAVED_NFTS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(Addr, String, String)>>>()?;
+    let mut user_saves: HashMap<Addr, u64> = HashMap::new();
+    for (addr, _, class_id) in saved.into_iter() {
+        if let Some(saved_ts) =
+            NFT_SAVERS.may_load(deps.storage, (class_id.clone(), addr.clone()))?
+        {
+            USER_SAVED_NFTS.save(deps.storage, (addr, class_id), &saved_ts)?;
+            continue;
+        }
+        NFT_SAVERS.save(deps.storage, (class_id.clone(), addr.clone()), &ts)?;
+        USER_SAVED_NFTS.save(deps.storage, (addr.clone(), class_id.clone()), &ts)?;
+        let count = NFT_SAVE_COUNTS.update(
+            deps.storage,
+            class_id.clone(),
+            |count| -> Result<_, ContractError> { Ok(count.unwrap_or_default().saturating_add(1)) },
+        )?;
+        update_most_saved(deps.storage, class_id, count)?;
+        *user_saves.entry(addr).or_default() += 1;
+    }
+    if !user_saves.is_empty() {
+        let mut total_saves: u64 = 0;
+        for (addr, saves) in user_saves.into_iter() {
+            if let Some(mut st) = USER_STATS.may_load(deps.storage, addr.clone())? {
+                st.saves = saves;
+                USER_STATS.save(deps.storage, addr, &st)?;
+            }
+            total_saves += saves;
+        }
+        STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
+            stats.saves = total_saves;
+            Ok(stats)
+        })?;
+    }
+
+    // The all time user boards get filled by SeedLeaderboards, windows fill up from new activity
+    let messages_until = MESSAGES
+        .keys(deps.storage, None, None, Order::Descending)
+        .next()
+        .transpose()?
+        .unwrap_or_default();
+    LEADERBOARD_SEED.save(
+        deps.storage,
+        &LeaderboardSeed {
+            users_after: None,
+            users_done: false,
+            messages_after: None,
+            messages_until,
         },
     )?;
 
-    // - append to state immediately, since its more efficient than checking if exists first
-    // - then tally all rate counts and remove from storage if found out of bounds item(s)
-    NFT_RATE_ATH.save(deps.storage, class_id.clone(), &rate_counts.all)?;
-    NFT_RATE_ATL.save(deps.storage, class_id.clone(), &rate_counts.all)?;
+    Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", prev.version)
+        .add_attribute("nfts_reindexed", updated.to_string()))
+}
+
+// "0.1.4" => [0, 1, 4], so versions compare numerically
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or_default())
+        .collect()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::ChangeConfig { config } => change_config(deps, env, info, *config),
+        ExecuteMsg::Message {
+            class_id,
+            message,
+            meta,
+            reply_to,
+        } => send_message(deps, env, info, class_id, message, meta, reply_to),
+        ExecuteMsg::EditMessage { id, message, meta } => {
+            edit_message(deps, env, info, id, message, meta)
+        }
+        ExecuteMsg::DeleteOwnMessage { id } => delete_own_message(deps, info, id),
+        ExecuteMsg::SetProfile {
+            display_name,
+            bio,
+            avatar,
+        } => set_profile(deps, env, info, display_name, bio, avatar),
+        ExecuteMsg::Follow { addr } => follow(deps, env, info, addr),
+        ExecuteMsg::Unfollow { addr } => unfollow(deps, info, addr),
+        ExecuteMsg::SendDirectMessage {
+            to,
+            class_id,
+            content,
+        } => send_direct_message(deps, env, info, to, class_id, content),
+        ExecuteMsg::BlockUser { addr } => block_user(deps, env, info, addr),
+        ExecuteMsg::UnblockUser { addr } => unblock_user(deps, info, addr),
+        ExecuteMsg::MarkNotificationsRead { up_to } => mark_notifications_read(deps, info, up_to),
+        ExecuteMsg::React { id, reaction } => react(deps, env, info, id, reaction),
+        ExecuteMsg::Unreact { id, reaction } => unreact(deps, info, id, reaction),
+        ExecuteMsg::Rate { class_id, v } => rate(deps, env, info, class_id, v),
+        ExecuteMsg::Share {
+            class_id,
+            token,
+            chain_id,
+        } => share(deps, env, info, class_id, token, chain_id),
+        ExecuteMsg::Save {
+            class_id,
+            collection,
+            note,
+        } => save(deps, env, info, class_id, collection, note),
+        ExecuteMsg::Unsave {
+            class_id,
+            collection,
+        } => unsave(deps, env, info, class_id, collection),
+        ExecuteMsg::CreateCollection { name } => create_collection(deps, env, info, name),
+        ExecuteMsg::RenameCollection { name, new_name } => {
+            rename_collection(deps, info, name, new_name)
+        }
+        ExecuteMsg::DeleteCollection { name } => delete_collection(deps, info, name),
+        ExecuteMsg::MoveSaved { class_id, from, to } => {
+            move_saved(deps, env, info, class_id, from, to)
+        }
+        ExecuteMsg::SetSavedVisibility { visibility } => {
+            set_saved_visibility(deps, info, visibility)
+        }
+        ExecuteMsg::PublishCollection {
+            name,
+            title,
+            description,
+        } => publish_collection(deps, env, info, name, title, description),
+        ExecuteMsg::UnpublishCollection { name } => unpublish_collection(deps, info, name),
+        ExecuteMsg::SaveGallery { owner, name } => save_gallery(deps, env, info, owner, name),
+        ExecuteMsg::UnsaveGallery { owner, name } => unsave_gallery(deps, info, owner, name),
+        ExecuteMsg::Boost { class_id } => boost(deps, env, info, class_id),
+        ExecuteMsg::ReportMessage { id, reason } => report_message(deps, env, info, id, reason),
+        ExecuteMsg::RemoveMessage { id } => remove_message(deps, info, id),
+        ExecuteMsg::ReviewMessage { id, remove } => review_message(deps, info, id, remove),
+        ExecuteMsg::SetCollectionStatus {
+            contract_addr,
+            status,
+        } => set_collection_status(deps, info, contract_addr, status),
+        ExecuteMsg::SetChainStatus { chain_id, status } => {
+            set_chain_status(deps, info, chain_id, status)
+        }
+        ExecuteMsg::HideNft { class_id } => hide_nft(deps, env, info, class_id),
+        ExecuteMsg::UnhideNft { class_id } => unhide_nft(deps, info, class_id),
+        ExecuteMsg::Withdraw { receiver } => withdraw(deps, env, info, receiver),
+        ExecuteMsg::BanUser { addr, purge } => ban_user(deps, env, info, addr, purge),
+        ExecuteMsg::PurgeUser { addr, limit } => purge_user(deps, info, addr, limit),
+        ExecuteMsg::MuteUser { addr, until } => mute_user(deps, env, info, addr, until),
+        ExecuteMsg::UnbanUser { addr } => unban_user(deps, info, addr),
+        ExecuteMsg::SetPaused { action, paused } => set_paused(deps, info, action, paused),
+        ExecuteMsg::SetBannedWords { add, remove } => set_banned_words(deps, info, add, remove),
+        ExecuteMsg::SeedLeaderboards { limit } => seed_leaderboards(deps, info, limit),
+    }
+}
+
+// Update configurations
+pub fn change_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: ConfigHr,
+) -> Result<Response, ContractError> {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can do this
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // the allowed set bounds how many counts a message carries
+    if let Some(reactions) = &config.reactions {
+        if reactions.len() > MAX_REACTIONS
+            || reactions
+                .iter()
+                .any(|r| r.trim().is_empty() || r.chars().count() > MAX_LEN_REACTION)
+        {
+            return Err(ContractError::CustomError {
+                val: "Invalid reactions".to_string(),
+            });
+        }
+    }
+
+    // escrowed bids were placed for windows of the current length
+    if config.boost_duration.is_some()
+        && config.boost_duration != c.boost_duration
+        && !escrowed_bids(deps.storage, env.block.time.seconds())?.is_empty()
+    {
+        return Err(ContractError::CustomError {
+            val: "Boost bids in escrow, cannot change boost_duration".to_string(),
+        });
+    }
+
+    let owner_addr = if let Some(owner) = config.owner.clone() {
+        deps.api.addr_validate(owner.as_str())?
+    } else {
+        info.sender.clone()
+    };
+
+    let updated = CONFIG.update(deps.storage, |mut state| -> Result<_, ContractError> {
+        if info.sender != state.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+        if let Some(share_fee) = config.share_fee {
+            state.share_fee = share_fee;
+        }
+        if let Some(unlock_messages) = config.unlock_messages {
+            state.unlock_messages = Some(unlock_messages);
+        }
+        if let Some(unlock_graffiti) = config.unlock_graffiti {
+            state.unlock_graffiti = Some(unlock_graffiti);
+        }
+        if let Some(unlock_share) = config.unlock_share {
+            state.unlock_share = Some(unlock_share);
+        }
+        if let Some(unlock_dms) = config.unlock_dms {
+            state.unlock_dms = Some(unlock_dms);
+        }
+        if let Some(rep_unlock_messages) = config.rep_unlock_messages {
+            state.rep_unlock_messages = Some(rep_unlock_messages);
+        }
+        if let Some(rep_unlock_graffiti) = config.rep_unlock_graffiti {
+            state.rep_unlock_graffiti = Some(rep_unlock_graffiti);
+        }
+        if let Some(rep_unlock_share) = config.rep_unlock_share {
+            state.rep_unlock_share = Some(rep_unlock_share);
+        }
+        if let Some(rate_decay) = config.rate_decay {
+            state.rate_decay = Some(rate_decay);
+        }
+        if let Some(report_threshold) = config.report_threshold {
+            state.report_threshold = Some(report_threshold);
+        }
+        if let Some(allowlist_only) = config.allowlist_only {
+            state.allowlist_only = Some(allowlist_only);
+        }
+        if let Some(slot_duration) = config.slot_duration {
+            state.slot_duration = Some(slot_duration);
+        }
+        if let Some(schedule_priority) = config.schedule_priority {
+            state.schedule_priority = Some(schedule_priority);
+        }
+        if let Some(boost_min_bid) = config.boost_min_bid {
+            state.boost_min_bid = Some(boost_min_bid);
+        }
+        if let Some(boost_duration) = config.boost_duration {
+            state.boost_duration = Some(boost_duration);
+        }
+        if let Some(message_edit_window) = config.message_edit_window {
+            state.message_edit_window = Some(message_edit_window);
+        }
+        if let Some(max_message_len) = config.max_message_len {
+            state.max_message_len = Some(max_message_len);
+        }
+        if let Some(max_meta_size) = config.max_meta_size {
+            state.max_meta_size = Some(max_meta_size);
+        }
+        if let Some(typed_meta) = config.typed_meta {
+            state.typed_meta = Some(typed_meta);
+        }
+        if let Some(reactions) = config.reactions {
+            state.reactions = Some(reactions);
+        }
+
+        // NOTE: Better to do a validated transfer flow, but in this case we're doing naive approach to SHIPPIT
+        if owner_addr != info.sender.clone() && config.owner.is_some() {
+            state.owner = owner_addr;
+        }
+
+        Ok(state)
+    })?;
+
+    // a lowered ratings unlock can hand badges to users already past it
+    let required = |c: &Config| -> Vec<u64> {
+        unlock_progress(c, &UserStats::default())
+            .into_iter()
+            .map(|p| p.ratings_required)
+            .collect()
+    };
+    let lowered: Vec<(u64, u64)> = required(&updated)
+        .into_iter()
+        .zip(required(&c))
+        .filter(|(after, before)| after < before)
+        .collect();
+    let mut events = vec![];
+    if let (Some(low), Some(high)) = (
+        lowered.iter().map(|(after, _)| *after).min(),
+        lowered.iter().map(|(_, before)| *before).max(),
+    ) {
+        let users = USER_RANKS
+            .sub_prefix(UserMetric::Ratings.as_str().to_string())
+            .keys(
+                deps.storage,
+                Some(Bound::inclusive((low, Addr::unchecked("")))),
+                Some(Bound::exclusive((high, Addr::unchecked("")))),
+                Order::Ascending,
+            )
+            .take(BADGE_BACKFILL_LIMIT)
+            .collect::<StdResult<Vec<(u64, Addr)>>>()?;
+        let ts = env.block.time.seconds();
+        for (_, addr) in users.into_iter() {
+            if let Some(st) = USER_STATS.may_load(deps.storage, addr.clone())? {
+                events.extend(check_milestones(deps.storage, &updated, &addr, &st, ts)?);
+            }
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "change_config")
+        .add_events(events))
+}
+
+pub fn rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    v: u8,
+) -> Result<Response, ContractError> {
+    check_not_paused(deps.storage, Action::Rate)?;
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+
+    // check NFT exists
+    if load_visible_nft(deps.storage, class_id.clone())?.is_none() {
+        return Err(ContractError::CustomError {
+            val: "NFT doesnt exist, cannot rate nothing silly human".to_string(),
+        });
+    }
+
+    // Check if already rated!
+    let rate_key = (class_id.clone(), info.sender.clone());
+    if NFT_RATINGS.has(deps.storage, rate_key.clone()) {
+        return Err(ContractError::CustomError {
+            val: "Already rated this nft, cannot rate again".to_string(),
+        });
+    }
+
+    // check value is valid (thank you GPT!! LOL)
+    if !(1..=5).contains(&v) {
+        return Err(ContractError::CustomError {
+            val: "Invalid rate value".to_string(),
+        });
+    }
+
+    let ts = env.block.time.seconds();
+    let sender_rate = Rate { ts, v };
+    NFT_RATINGS.save(deps.storage, rate_key, &sender_rate)?;
+    record_activity(
+        deps.storage,
+        &info.sender,
+        Activity {
+            id: None,
+            kind: ActivityKind::Rate,
+            from: info.sender.clone(),
+            class_id: class_id.clone(),
+            ts,
+            v: Some(v),
+            msg_id: None,
+        },
+    )?;
+
+    // get the previous ts offset via remainders
+    let day_remainder = ts % DAY_IN_SECONDS;
+    let year_remainder = ts % (DAY_IN_SECONDS * 366);
+    let last_day_ts = ts.saturating_sub(day_remainder);
+    let last_year_ts = ts.saturating_sub(year_remainder);
+
+    // Truncate day caches older than 1 year ago
+    let ath_keys = NFT_RATE_DAY_ATH
+        .keys(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(last_year_ts)),
+            Order::Descending,
+        )
+        .map(|res| res.unwrap())
+        .collect::<Vec<_>>();
+    let atl_keys = NFT_RATE_DAY_ATL
+        .keys(
+            deps.storage,
+            None,
+            Some(Bound::inclusive(last_year_ts)),
+            Order::Descending,
+        )
+        .map(|res| res.unwrap())
+        .collect::<Vec<_>>();
+
+    for hk in ath_keys.into_iter() {
+        NFT_RATE_DAY_ATH.remove(deps.storage, hk);
+    }
+    for lk in atl_keys.into_iter() {
+        NFT_RATE_DAY_ATL.remove(deps.storage, lk);
+    }
+
+    // compute total rate counts
+    let rate_counts = NFT_RATE_COUNTS.update(
+        deps.storage,
+        class_id.clone(),
+        |counts| -> Result<_, ContractError> {
+            match counts {
+                Some(rc) => {
+                    let mut r = rc;
+                    // update all counts
+                    r.all.ts = 0;
+                    r.all.total = r.all.total.saturating_add(1);
+                    r.all.sum = r.all.sum.saturating_add(v as u64);
+
+                    if r.day.ts + last_day_ts > ts {
+                        // Still within range
+                        r.day.ts = ts;
+                        r.day.total = r.day.total.saturating_add(1);
+                        r.day.sum = r.day.sum.saturating_add(v as u64);
+                    } else {
+                        // outside range, start fresh
+                        r.day.ts = last_day_ts;
+                        r.day.total = 1;
+                        r.day.sum = v as u64;
+                    }
+                    Ok(r)
+                }
+                None => {
+                    let default_rc = RateCount {
+                        ts,
+                        sum: v as u64,
+                        total: 1,
+                    };
+                    Ok(RateCounts {
+                        all: default_rc.clone(),
+                        day: default_rc,
+                    })
+                }
+            }
+        },
+    )?;
+
+    // - append to state immediately, since its more efficient than checking if exists first
+    // - then tally all rate counts and remove from storage if found out of bounds item(s)
+    NFT_RATE_ATH.save(deps.storage, class_id.clone(), &rate_counts.all)?;
+    NFT_RATE_ATL.save(deps.storage, class_id.clone(), &rate_counts.all)?;
+
+    let mut all_ath = NFT_RATE_ATH
+        .range(deps.storage, None, None, Order::Descending)
+        .map(|res| res.unwrap())
+        .collect::<Vec<(String, RateCount)>>();
+    let mut all_atl = NFT_RATE_ATL
+        .range(deps.storage, None, None, Order::Descending)
+        .map(|res| res.unwrap())
+        .collect::<Vec<(String, RateCount)>>();
+
+    // sort then truncate as needed
+    all_ath.sort_by(compare_desc);
+    let top_share = all_ath
+        .iter()
+        .take(BADGE_TOP_SHARE_RANK)
+        .any(|(k, _)| *k == class_id);
+    if all_ath.len() > MAX_LEN_ALL_TIME {
+        for (k, _) in all_ath[MAX_LEN_ALL_TIME..].iter() {
+            NFT_RATE_ATH.remove(deps.storage, k.to_string());
+        }
+    }
+    all_atl.sort_by(compare_asc);
+    if all_ath.len() > MAX_LEN_ALL_TIME {
+        for (k, _) in all_ath[MAX_LEN_ALL_TIME..].iter() {
+            NFT_RATE_ATL.remove(deps.storage, k.to_string());
+        }
+    }
+
+    // sort days then truncate as needed
+    NFT_RATE_DAY_ATH.update(
+        deps.storage,
+        last_day_ts,
+        |rates| -> Result<_, ContractError> {
+            match rates {
+                Some(s) => {
+                    let mut days: Vec<_> = s.into_iter().collect();
+                    days.sort_by(compare_desc);
+                    if days.len() > MAX_LEN_DAY {
+                        days.truncate(MAX_LEN_DAY + 1);
+                    }
+                    let days_map: HashMap<String, RateCount> = days.into_iter().collect();
+                    Ok(days_map)
+                }
+                None => {
+                    let mut n = HashMap::new();
+                    n.insert(
+                        class_id.clone(),
+                        RateCount {
+                            ts,
+                            sum: v as u64,
+                            total: 1,
+                        },
+                    );
+                    Ok(n)
+                }
+            }
+        },
+    )?;
+    NFT_RATE_DAY_ATL.update(
+        deps.storage,
+        last_day_ts,
+        |rates| -> Result<_, ContractError> {
+            match rates {
+                Some(s) => {
+                    let mut days: Vec<_> = s.into_iter().collect();
+                    days.sort_by(compare_asc);
+                    if days.len() > MAX_LEN_DAY {
+                        days.truncate(MAX_LEN_DAY + 1);
+                    }
+                    let days_map: HashMap<String, RateCount> = days.into_iter().collect();
+                    Ok(days_map)
+                }
+                None => {
+                    let mut n = HashMap::new();
+                    n.insert(
+                        class_id.clone(),
+                        RateCount {
+                            ts,
+                            sum: v as u64,
+                            total: 1,
+                        },
+                    );
+                    Ok(n)
+                }
+            }
+        },
+    )?;
+
+    // update user stats
+    let active_day = ts.saturating_sub(ts % CALENDAR_DAY_IN_SECONDS);
+    let prev_active_day = USER_STATS
+        .may_load(deps.storage, info.sender.clone())?
+        .and_then(|st| st.last_active_day);
+    let user_stats = USER_STATS.update(
+        deps.storage,
+        info.sender.clone(),
+        |stats| -> Result<_, ContractError> {
+            match stats {
+                Some(s) => {
+                    let mut st = s;
+                    st.last_rate_ts = ts;
+                    st.ratings = st.ratings.saturating_add(1);
+                    Ok(update_streak(st, active_day))
+                }
+                None => Ok(update_streak(
+                    UserStats {
+                        last_rate_ts: ts,
+                        ratings: 1,
+                        saves: 0,
+                        shares: 0,
+                        ..UserStats::default()
+                    },
+                    active_day,
+                )),
+            }
+        },
+    )?;
+    bump_user_score(deps.storage, UserMetric::Ratings, &info.sender, ts, true)?;
+    USER_ACTIVE_DAYS.update(
+        deps.storage,
+        (info.sender.clone(), active_day),
+        |count| -> StdResult<_> { Ok(count.unwrap_or_default().saturating_add(1)) },
+    )?;
+    if let Some(prev) = prev_active_day.filter(|prev| *prev != active_day) {
+        set_user_score(deps.storage, &streak_board_key(prev), &info.sender, 0)?;
+    }
+    set_user_score(
+        deps.storage,
+        &streak_board_key(active_day),
+        &info.sender,
+        user_stats.current_streak.unwrap_or_default(),
+    )?;
+    for (metric, score) in [
+        (UserMetric::LongestStreak, user_stats.longest_streak),
+        (UserMetric::ActiveDays, user_stats.active_days),
+    ] {
+        set_user_score(
+            deps.storage,
+            metric.as_str(),
+            &info.sender,
+            score.unwrap_or_default(),
+        )?;
+    }
+
+    // early raters get scored against the average once enough ratings are in
+    if rate_counts.all.total <= REPUTATION_EARLY_RATERS {
+        EARLY_RATINGS.save(deps.storage, (class_id.clone(), info.sender.clone()), &v)?;
+    }
+    let c = CONFIG.load(deps.storage)?;
+    let mut events = vec![];
+    if rate_counts.all.total >= REPUTATION_SETTLE_RATINGS
+        && !REPUTATION_SETTLED.has(deps.storage, class_id.clone())
+    {
+        events.extend(settle_reputation(
+            deps.storage,
+            &c,
+            class_id.clone(),
+            &rate_counts.all,
+            ts,
+        )?);
+    }
+
+    // badges
+    events.extend(check_milestones(
+        deps.storage,
+        &c,
+        &info.sender,
+        &user_stats,
+        ts,
+    )?);
+    if top_share {
+        if let Some(sharer) = NFTS
+            .may_load(deps.storage, class_id)?
+            .and_then(|n| n.sharer)
+        {
+            events.extend(award_badge(
+                deps.storage,
+                &sharer,
+                BadgeKind::TopTenShare,
+                ts,
+            )?);
+        }
+    }
+
+    // update stats
+    STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
+        stats.ratings = stats.ratings.saturating_add(1);
+        Ok(stats)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "rate")
+        .add_events(events))
+}
+
+pub fn share(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    token: TokenUri,
+    chain_id: Option<String>,
+) -> Result<Response, ContractError> {
+    check_not_paused(deps.storage, Action::Share)?;
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+
+    // check NFT doesnt exist
+    if NFTS.has(deps.storage, class_id.clone()) {
+        return Err(ContractError::CustomError {
+            val: "NFT already exists, cannot share again".to_string(),
+        });
+    }
+    let c = CONFIG.load(deps.storage)?;
+
+    // owner can share directly, otherwise check
+    if info.sender != c.owner {
+        let user_stats = USER_STATS.may_load(deps.storage, info.sender.clone())?;
+        if let Some(user_stats) = user_stats {
+            if !has_unlocked(&user_stats, c.unlock_share.unwrap_or(5), c.rep_unlock_share) {
+                return Err(ContractError::CustomError {
+                    val: "Not enough ratings, cannot share".to_string(),
+                });
+            }
+
+            // Check max shares!!
+            if user_stats.shares > DEFAULT_USER_MAX_SHARES {
+                return Err(ContractError::CustomError {
+                    val: "Maximum allowed shares reached, cannot share".to_string(),
+                });
+            }
+        } else {
+            return Err(ContractError::CustomError {
+                val: "No user prefs, cannot share".to_string(),
+            });
+        }
+
+        // check collection & chain are allowed
+        if !is_collection_eligible(
+            deps.storage,
+            c.allowlist_only.unwrap_or(false),
+            token.contract_addr.as_str(),
+            chain_id.as_deref(),
+        )? {
+            return Err(ContractError::CustomError {
+                val: "Collection not allowed, cannot share".to_string(),
+            });
+        }
+
+        // check user provided adequate fee
+        if !has_coins(&info.funds, &c.share_fee) {
+            return Err(ContractError::CustomError {
+                val: "Invalid share fee amount, cannot share".to_string(),
+            });
+        }
+    }
+
+    // create new NFT records, index is the 0-based LIST position
+    let index = LIST.len(deps.storage)?;
+    LIST.push_back(deps.storage, &class_id)?;
+
+    let nft = Nft {
+        class_id: class_id.clone(),
+        token,
+        chain_id,
+        index: Some(u64::from(index)),
+        hidden: None,
+        sharer: Some(info.sender.clone()),
+    };
+
+    NFTS.save(deps.storage, class_id.clone(), &nft)?;
+    record_activity(
+        deps.storage,
+        &info.sender,
+        Activity {
+            id: None,
+            kind: ActivityKind::Share,
+            from: info.sender.clone(),
+            class_id: class_id.clone(),
+            ts: env.block.time.seconds(),
+            v: None,
+            msg_id: None,
+        },
+    )?;
+    bump_user_score(
+        deps.storage,
+        UserMetric::Shares,
+        &info.sender,
+        env.block.time.seconds(),
+        true,
+    )?;
+
+    // queue up for the "current" rotation
+    let now = env.block.time.seconds();
+    let slot = c.slot_duration.unwrap_or(DEFAULT_SLOT_DURATION);
+    advance_schedule(deps.storage, now, slot)?;
+    let rank = match c
+        .schedule_priority
+        .clone()
+        .unwrap_or(SchedulePriority::Fifo)
+    {
+        SchedulePriority::Fifo => 0,
+        SchedulePriority::Owner => u64::from(info.sender != c.owner),
+        SchedulePriority::Fee => {
+            let paid: u128 = info
+                .funds
+                .iter()
+                .filter(|coin| coin.denom == c.share_fee.denom)
+                .map(|coin| coin.amount.u128())
+                .sum();
+            u64::MAX - paid.min(u64::MAX as u128) as u64
+        }
+    };
+    let seq = SCHEDULE_SEQ.may_load(deps.storage)?.unwrap_or_default();
+    SCHEDULE_SEQ.save(deps.storage, &(seq + 1))?;
+    SCHEDULE_PENDING.save(deps.storage, (rank, seq), &PendingNft { class_id, ts: now })?;
+    advance_schedule(deps.storage, now, slot)?;
+
+    // update user stats
+    let user_stats = USER_STATS.update(
+        deps.storage,
+        info.sender.clone(),
+        |stats| -> Result<_, ContractError> {
+            match stats {
+                Some(s) => {
+                    let mut st = s;
+                    st.shares = st.shares.saturating_add(1);
+                    Ok(st)
+                }
+                None => Ok(UserStats {
+                    last_rate_ts: env.block.time.seconds(),
+                    ratings: 0,
+                    saves: 0,
+                    shares: 1,
+                    ..UserStats::default()
+                }),
+            }
+        },
+    )?;
+
+    let events = check_milestones(
+        deps.storage,
+        &c,
+        &info.sender,
+        &user_stats,
+        env.block.time.seconds(),
+    )?;
+
+    // update stats
+    STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
+        stats.nfts = stats.nfts.saturating_add(1);
+        Ok(stats)
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "share")
+        .add_events(events))
+}
+
+pub fn save(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    collection: Option<String>,
+    note: Option<String>,
+) -> Result<Response, ContractError> {
+    check_not_paused(deps.storage, Action::Save)?;
+
+    // check NFT doesnt exist
+    if load_visible_nft(deps.storage, class_id.clone())?.is_none() {
+        return Err(ContractError::CustomError {
+            val: "NFT doesnt exist, cannot save".to_string(),
+        });
+    }
+    if let Some(note) = &note {
+        if note.chars().count() > MAX_LEN_MESSAGE {
+            return Err(ContractError::CustomError {
+                val: "Note too long".to_string(),
+            });
+        }
+    }
+    let c = CONFIG.load(deps.storage)?;
+    // check user provided adequate fee
+    if info.sender != c.owner && !has_coins(&info.funds, &c.save_fee) {
+        return Err(ContractError::CustomError {
+            val: "Invalid save fee amount, cannot save".to_string(),
+        });
+    }
+
+    let ts = env.block.time.seconds();
+    let collection = collection.unwrap_or_else(|| DEFAULT_SAVED_COLLECTION.to_string());
+    let mut sc = load_saved_collection(deps.storage, &info.sender, &collection, ts)?;
+    let saved_key = (info.sender.clone(), collection.clone(), class_id.clone());
+    if !SAVED_NFTS.has(deps.storage, saved_key.clone()) {
+        check_collection_size(&sc)?;
+        sc.count = sc.count.saturating_add(1);
+        SAVED_COLLECTIONS.save(deps.storage, (info.sender.clone(), collection), &sc)?;
+    }
+    // re-saving only updates the note, counts stay put
+    SAVED_NFTS.save(deps.storage, saved_key, &SavedMeta { ts, note })?;
+    add_saver(deps.storage, &info.sender, class_id, ts)?;
+
+    Ok(Response::new().add_attribute("method", "save"))
+}
+
+pub fn unsave(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    class_id: String,
+    collection: Option<String>,
+) -> Result<Response, ContractError> {
+    // check NFT exists
+    if !NFTS.has(deps.storage, class_id.clone()) {
+        return Err(ContractError::CustomError {
+            val: "NFT doesnt exist, cannot unsave".to_string(),
+        });
+    }
+    let collection = collection.unwrap_or_else(|| DEFAULT_SAVED_COLLECTION.to_string());
+    let saved_key = (info.sender.clone(), collection.clone(), class_id.clone());
+
+    if SAVED_NFTS.has(deps.storage, saved_key.clone()) {
+        SAVED_NFTS.remove(deps.storage, saved_key);
+        SAVED_COLLECTIONS.update(
+            deps.storage,
+            (info.sender.clone(), collection),
+            |sc| -> Result<_, ContractError> {
+                let mut sc = sc.ok_or_else(|| StdError::not_found("SavedCollection"))?;
+                sc.count = sc.count.saturating_sub(1);
+                Ok(sc)
+            },
+        )?;
+        remove_saver(deps.storage, &info.sender, class_id)?;
+    }
+
+    Ok(Response::new().add_attribute("method", "unsave"))
+}
+
+pub fn create_collection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    validate_collection_name(&name)?;
+    if SAVED_COLLECTIONS.has(deps.storage, (info.sender.clone(), name.clone())) {
+        return Err(ContractError::CustomError {
+            val: "Collection already exists".to_string(),
+        });
+    }
+    check_collection_limit(deps.storage, &info.sender)?;
+    SAVED_COLLECTIONS.save(
+        deps.storage,
+        (info.sender, name.clone()),
+        &SavedCollection {
+            name: name.clone(),
+            ts: env.block.time.seconds(),
+            count: 0,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "create_collection")
+        .add_attribute("name", name))
+}
+
+pub fn rename_collection(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    new_name: String,
+) -> Result<Response, ContractError> {
+    validate_collection_name(&new_name)?;
+    let mut sc =
+        match SAVED_COLLECTIONS.may_load(deps.storage, (info.sender.clone(), name.clone()))? {
+            Some(sc) => sc,
+            None => {
+                return Err(ContractError::CustomError {
+                    val: "Collection doesnt exist".to_string(),
+                })
+            }
+        };
+    if SAVED_COLLECTIONS.has(deps.storage, (info.sender.clone(), new_name.clone())) {
+        return Err(ContractError::CustomError {
+            val: "Collection already exists".to_string(),
+        });
+    }
+    check_collection_rewrite(&sc)?;
+
+    // move every saved entry over to the new key
+    let saved = SAVED_NFTS
+        .prefix((info.sender.clone(), name.clone()))
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<(String, SavedMeta)>>>()?;
+    for (class_id, meta) in saved.into_iter() {
+        SAVED_NFTS.remove(
+            deps.storage,
+            (info.sender.clone(), name.clone(), class_id.clone()),
+        );
+        SAVED_NFTS.save(
+            deps.storage,
+            (info.sender.clone(), new_name.clone(), class_id),
+            &meta,
+        )?;
+    }
+    SAVED_COLLECTIONS.remove(deps.storage, (info.sender.clone(), name.clone()));
+    sc.name = new_name.clone();
+    SAVED_COLLECTIONS.save(deps.storage, (info.sender.clone(), new_name.clone()), &sc)?;
+
+    // a published gallery follows its collection
+    if let Some(mut g) = GALLERIES.may_load(deps.storage, (info.sender.clone(), name.clone()))? {
+        GALLERIES.remove(deps.storage, (info.sender.clone(), name));
+        g.collection = new_name.clone();
+        GALLERIES.save(deps.storage, (info.sender, new_name.clone()), &g)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "rename_collection")
+        .add_attribute("name", new_name))
+}
+
+pub fn delete_collection(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    match SAVED_COLLECTIONS.may_load(deps.storage, (info.sender.clone(), name.clone()))? {
+        Some(sc) => check_collection_rewrite(&sc)?,
+        None => {
+            return Err(ContractError::CustomError {
+                val: "Collection doesnt exist".to_string(),
+            })
+        }
+    }
+
+    let class_ids = SAVED_NFTS
+        .prefix((info.sender.clone(), name.clone()))
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<String>>>()?;
+    SAVED_COLLECTIONS.remove(deps.storage, (info.sender.clone(), name.clone()));
+    GALLERIES.remove(deps.storage, (info.sender.clone(), name.clone()));
+    for class_id in class_ids.into_iter() {
+        SAVED_NFTS.remove(
+            deps.storage,
+            (info.sender.clone(), name.clone(), class_id.clone()),
+        );
+        remove_saver(deps.storage, &info.sender, class_id)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "delete_collection")
+        .add_attribute("name", name))
+}
+
+// Move a saved NFT between two of the sender's collections, keeping its meta
+pub fn move_saved(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    from: String,
+    to: String,
+) -> Result<Response, ContractError> {
+    let from_key = (info.sender.clone(), from.clone(), class_id.clone());
+    let meta = match SAVED_NFTS.may_load(deps.storage, from_key.clone())? {
+        Some(meta) => meta,
+        None => {
+            return Err(ContractError::CustomError {
+                val: "NFT not saved in collection".to_string(),
+            })
+        }
+    };
+    let to_key = (info.sender.clone(), to.clone(), class_id);
+    if SAVED_NFTS.has(deps.storage, to_key.clone()) {
+        return Err(ContractError::CustomError {
+            val: "NFT already saved in collection".to_string(),
+        });
+    }
+    let ts = env.block.time.seconds();
+    let mut to_sc = load_saved_collection(deps.storage, &info.sender, &to, ts)?;
+    check_collection_size(&to_sc)?;
+
+    SAVED_NFTS.remove(deps.storage, from_key);
+    SAVED_NFTS.save(deps.storage, to_key, &meta)?;
+    SAVED_COLLECTIONS.update(
+        deps.storage,
+        (info.sender.clone(), from),
+        |sc| -> Result<_, ContractError> {
+            let mut sc = sc.ok_or_else(|| StdError::not_found("SavedCollection"))?;
+            sc.count = sc.count.saturating_sub(1);
+            Ok(sc)
+        },
+    )?;
+    to_sc.count = to_sc.count.saturating_add(1);
+    SAVED_COLLECTIONS.save(deps.storage, (info.sender, to), &to_sc)?;
+
+    Ok(Response::new().add_attribute("method", "move_saved"))
+}
+
+pub fn set_saved_visibility(
+    deps: DepsMut,
+    info: MessageInfo,
+    visibility: Visibility,
+) -> Result<Response, ContractError> {
+    SAVED_VISIBILITY.save(deps.storage, info.sender, &visibility)?;
+
+    Ok(Response::new().add_attribute("method", "set_saved_visibility"))
+}
+
+// Publish one of the sender's collections as a public gallery, re-publishing updates the text
+pub fn publish_collection(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    title: String,
+    description: Option<String>,
+) -> Result<Response, ContractError> {
+    if !SAVED_COLLECTIONS.has(deps.storage, (info.sender.clone(), name.clone())) {
+        return Err(ContractError::CustomError {
+            val: "Collection doesnt exist".to_string(),
+        });
+    }
+    if title.trim().is_empty() || title.chars().count() > MAX_LEN_GALLERY_TITLE {
+        return Err(ContractError::CustomError {
+            val: "Invalid gallery title".to_string(),
+        });
+    }
+    if let Some(description) = &description {
+        if description.chars().count() > MAX_LEN_GALLERY_DESCRIPTION {
+            return Err(ContractError::CustomError {
+                val: "Gallery description too long".to_string(),
+            });
+        }
+    }
+
+    let key = (info.sender.clone(), name.clone());
+    let saves = GALLERIES
+        .may_load(deps.storage, key.clone())?
+        .map(|g| g.saves)
+        .unwrap_or_default();
+    GALLERIES.save(
+        deps.storage,
+        key,
+        &Gallery {
+            owner: info.sender,
+            collection: name.clone(),
+            title,
+            description,
+            ts: env.block.time.seconds(),
+            saves,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "publish_collection")
+        .add_attribute("name", name))
+}
+
+pub fn unpublish_collection(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+) -> Result<Response, ContractError> {
+    GALLERIES.remove(deps.storage, (info.sender, name.clone()));
+
+    Ok(Response::new()
+        .add_attribute("method", "unpublish_collection")
+        .add_attribute("name", name))
+}
+
+pub fn save_gallery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: Addr,
+    name: String,
+) -> Result<Response, ContractError> {
+    check_not_paused(deps.storage, Action::Save)?;
+
+    let gallery_key = (owner.clone(), name.clone());
+    let mut gallery = match GALLERIES.may_load(deps.storage, gallery_key.clone())? {
+        Some(g) => g,
+        None => {
+            return Err(ContractError::CustomError {
+                val: "Gallery doesnt exist, cannot save".to_string(),
+            })
+        }
+    };
+    let saved_key = (info.sender, owner, name);
+    if !SAVED_GALLERIES.has(deps.storage, saved_key.clone()) {
+        SAVED_GALLERIES.save(deps.storage, saved_key, &env.block.time.seconds())?;
+        gallery.saves = gallery.saves.saturating_add(1);
+        GALLERIES.save(deps.storage, gallery_key, &gallery)?;
+    }
+
+    Ok(Response::new().add_attribute("method", "save_gallery"))
+}
+
+pub fn unsave_gallery(
+    deps: DepsMut,
+    info: MessageInfo,
+    owner: Addr,
+    name: String,
+) -> Result<Response, ContractError> {
+    let saved_key = (info.sender, owner.clone(), name.clone());
+    if SAVED_GALLERIES.has(deps.storage, saved_key.clone()) {
+        SAVED_GALLERIES.remove(deps.storage, saved_key);
+        if let Some(mut g) = GALLERIES.may_load(deps.storage, (owner.clone(), name.clone()))? {
+            g.saves = g.saves.saturating_sub(1);
+            GALLERIES.save(deps.storage, (owner, name), &g)?;
+        }
+    }
+
+    Ok(Response::new().add_attribute("method", "unsave_gallery"))
+}
+
+pub fn boost(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+) -> Result<Response, ContractError> {
+    check_not_paused(deps.storage, Action::Boost)?;
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+
+    if load_visible_nft(deps.storage, class_id.clone())?.is_none() {
+        return Err(ContractError::CustomError {
+            val: "NFT doesnt exist, cannot boost".to_string(),
+        });
+    }
+    let c = CONFIG.load(deps.storage)?;
+    let min_bid = match c.boost_min_bid.clone() {
+        Some(min_bid) => min_bid,
+        None => {
+            return Err(ContractError::CustomError {
+                val: "Boosting not enabled".to_string(),
+            })
+        }
+    };
+
+    // only a single coin of the boost denom
+    let amount = match info.funds.as_slice() {
+        [coin] if coin.denom == min_bid.denom => coin.clone(),
+        _ => {
+            return Err(ContractError::CustomError {
+                val: format!("Invalid bid funds, send only {}", min_bid.denom),
+            })
+        }
+    };
+    if amount.amount < min_bid.amount {
+        return Err(ContractError::CustomError {
+            val: "Bid below minimum, cannot boost".to_string(),
+        });
+    }
+
+    // bids are always for the next window
+    let (_, next_start) = boost_window(&c, env.block.time.seconds());
+    let (start, end) = boost_window(&c, next_start);
+    let mut res = Response::new().add_attribute("method", "boost");
+    if let Some(prev) = BOOST_BIDS.may_load(deps.storage, start)? {
+        // a bid in a previous boost denom can't be compared, it just gets refunded
+        if prev.amount.denom == amount.denom && amount.amount <= prev.amount.amount {
+            return Err(ContractError::CustomError {
+                val: "Bid too low, must outbid the current bid".to_string(),
+            });
+        }
+        res = res
+            .add_attribute("refunded", prev.bidder.to_string())
+            .add_submessage(SubMsg::new(BankMsg::Send {
+                to_address: prev.bidder.to_string(),
+                amount: vec![prev.amount],
+            }));
+    }
+
+    BOOST_BIDS.save(
+        deps.storage,
+        start,
+        &BoostBid {
+            class_id: class_id.clone(),
+            bidder: info.sender,
+            amount: amount.clone(),
+            window: start / (end - start),
+            start,
+            end,
+        },
+    )?;
+
+    Ok(res
+        .add_attribute("class_id", class_id)
+        .add_attribute("amount", amount.to_string()))
+}
+
+pub fn send_message(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    class_id: String,
+    message: String,
+    meta: Option<Binary>,
+    reply_to: Option<MessageId>,
+) -> Result<Response, ContractError> {
+    check_not_paused(deps.storage, Action::Message)?;
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+
+    // check NFT exists
+    if load_visible_nft(deps.storage, class_id.clone())?.is_none() {
+        return Err(ContractError::CustomError {
+            val: "NFT doesnt exist, cannot message".to_string(),
+        });
+    }
+    let c = CONFIG.load(deps.storage)?;
+    validate_message(deps.storage, &c, &message)?;
+    validate_meta(deps.api, &c, &meta)?;
+    // Get the prefs of receiver, to filter out thangs
+    let user_stats = USER_STATS.may_load(deps.storage, info.sender.clone())?;
+    if let Some(user_stats) = user_stats {
+        if !has_unlocked(
+            &user_stats,
+            c.unlock_messages.unwrap_or(5),
+            c.rep_unlock_messages,
+        ) {
+            return Err(ContractError::CustomError {
+                val: "Not enough ratings, cannot post".to_string(),
+            });
+        }
+    } else {
+        return Err(ContractError::CustomError {
+            val: "No user prefs, cannot post".to_string(),
+        });
+    }
+
+    // replies stay on the same NFT as their parent
+    let parent_from = match reply_to {
+        Some(parent_id) => match MESSAGES.may_load(deps.storage, parent_id)? {
+            Some(parent) if parent.class_id == class_id => Some(parent.from),
+            _ => {
+                return Err(ContractError::CustomError {
+                    val: "Parent message doesnt exist, cannot reply".to_string(),
+                })
+            }
+        },
+        None => None,
+    };
+    let mentions = parse_mentions(deps.api, &message, &meta);
+
+    // ids follow block time, but stay unique when a block has several messages
+    let ts = env.block.time.seconds();
+    let last_id = match LAST_MESSAGE_ID.may_load(deps.storage)? {
+        Some(last_id) => Some(last_id),
+        None => MESSAGES
+            .keys(deps.storage, None, None, Order::Descending)
+            .next()
+            .transpose()?,
+    };
+    let msg_id = match last_id {
+        Some(last_id) if last_id >= ts => last_id + 1,
+        _ => ts,
+    };
+    LAST_MESSAGE_ID.save(deps.storage, &msg_id)?;
+    let new_msg = Message {
+        id: Some(msg_id),
+        ts,
+        class_id: class_id.clone(),
+        message,
+        from: info.sender,
+        meta,
+        reply_to,
+        reactions: None,
+        edited_at: None,
+    };
+    if let Some(parent_id) = reply_to {
+        MESSAGE_REPLIES.save(deps.storage, (parent_id, msg_id), &Empty {})?;
+    }
+
+    MESSAGES.save(deps.storage, msg_id, &new_msg)?;
+    NFT_MESSAGES.save(deps.storage, (class_id.clone(), msg_id), &Empty {})?;
+    record_activity(
+        deps.storage,
+        &new_msg.from,
+        Activity {
+            id: None,
+            kind: ActivityKind::Message,
+            from: new_msg.from.clone(),
+            class_id: class_id.clone(),
+            ts,
+            v: None,
+            msg_id: Some(msg_id),
+        },
+    )?;
+
+    // nobody gets notified about their own message, or twice for one message
+    let mut notified: Vec<Addr> = vec![new_msg.from.clone()];
+    let targets = parent_from
+        .map(|a| (a, NotificationKind::Reply))
+        .into_iter()
+        .chain(mentions.into_iter().map(|a| (a, NotificationKind::Mention)));
+    for (addr, kind) in targets {
+        if notified.contains(&addr) {
+            continue;
+        }
+        notify(
+            deps.storage,
+            &addr,
+            Notification {
+                id: None,
+                kind,
+                msg_id,
+                class_id: class_id.clone(),
+                from: new_msg.from.clone(),
+                ts,
+                read: None,
+            },
+        )?;
+        notified.push(addr);
+    }
+
+    bump_user_score(deps.storage, UserMetric::Messages, &new_msg.from, ts, true)?;
+
+    // update stats
+    STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
+        stats.messages = stats.messages.saturating_add(1);
+        Ok(stats)
+    })?;
+
+    Ok(Response::new().add_attribute("method", "message"))
+}
+
+pub fn edit_message(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: MessageId,
+    message: String,
+    meta: Option<Binary>,
+) -> Result<Response, ContractError> {
+    check_not_paused(deps.storage, Action::Message)?;
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+
+    let mut msg = match MESSAGES.may_load(deps.storage, id)? {
+        Some(msg) => msg,
+        None => {
+            return Err(ContractError::CustomError {
+                val: "Message doesnt exist, cannot edit".to_string(),
+            })
+        }
+    };
+    if msg.from != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let c = CONFIG.load(deps.storage)?;
+    let window = c.message_edit_window.unwrap_or(DEFAULT_MESSAGE_EDIT_WINDOW);
+    if window == 0 || env.block.time.seconds() > msg.ts.saturating_add(window) {
+        return Err(ContractError::CustomError {
+            val: "Edit window has passed".to_string(),
+        });
+    }
+    validate_message(deps.storage, &c, &message)?;
+    validate_meta(deps.api, &c, &meta)?;
+
+    msg.message = message;
+    msg.meta = meta;
+    msg.edited_at = Some(env.block.time.seconds());
+    MESSAGES.save(deps.storage, id, &msg)?;
+
+    Ok(Response::new().add_attribute("method", "edit_message"))
+}
+
+pub fn delete_own_message(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: MessageId,
+) -> Result<Response, ContractError> {
+    match MESSAGES.may_load(deps.storage, id)? {
+        Some(msg) if msg.from == info.sender => {}
+        Some(_) => return Err(ContractError::Unauthorized {}),
+        None => {
+            return Err(ContractError::CustomError {
+                val: "Message doesnt exist, cannot delete".to_string(),
+            })
+        }
+    }
+
+    if delete_message(deps.storage, id)?.is_some() {
+        // update stats
+        STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
+            stats.messages = stats.messages.saturating_sub(1);
+            Ok(stats)
+        })?;
+    }
+
+    Ok(Response::new().add_attribute("method", "delete_own_message"))
+}
+
+pub fn set_profile(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    display_name: Option<String>,
+    bio: Option<String>,
+    avatar: Option<Avatar>,
+) -> Result<Response, ContractError> {
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+
+    if let Some(name) = display_name.as_ref() {
+        validate_display_name(name)?;
+        match PROFILE_NAMES.may_load(deps.storage, name.to_ascii_lowercase())? {
+            Some(owner) if owner != info.sender => {
+                return Err(ContractError::CustomError {
+                    val: "Display name already taken".to_string(),
+                })
+            }
+            _ => {}
+        }
+    }
+    if let Some(bio) = bio.as_ref() {
+        if bio.chars().count() > MAX_LEN_BIO || bio.chars().any(|ch| ch.is_control()) {
+            return Err(ContractError::CustomError {
+                val: "Invalid bio".to_string(),
+            });
+        }
+    }
+    if let Some(Avatar::Shared { class_id }) = avatar.as_ref() {
+        if load_visible_nft(deps.storage, class_id.clone())?.is_none() {
+            return Err(ContractError::CustomError {
+                val: "NFT doesnt exist, cannot use as avatar".to_string(),
+            });
+        }
+    }
+    // same collection rules as sharing
+    if let Some(Avatar::Token { token }) = avatar.as_ref() {
+        let c = CONFIG.load(deps.storage)?;
+        let contract_addr = deps.api.addr_validate(token.contract_addr.as_str())?;
+        let uri_ok = token
+            .data_uri
+            .as_ref()
+            .map(|uri| !uri.trim().is_empty() && uri.chars().count() <= MAX_LEN_META_URL)
+            .unwrap_or(true);
+        if token.id.trim().is_empty()
+            || !uri_ok
+            || !is_collection_eligible(
+                deps.storage,
+                c.allowlist_only.unwrap_or(false),
+                contract_addr.as_str(),
+                Some(env.block.chain_id.as_str()),
+            )?
+        {
+            return Err(ContractError::CustomError {
+                val: "Invalid token, cannot use as avatar".to_string(),
+            });
+        }
+    }
+
+    // free up the previous name when it changes
+    if let Some(prev) = PROFILES.may_load(deps.storage, info.sender.clone())? {
+        if let Some(prev_name) = prev.display_name {
+            PROFILE_NAMES.remove(deps.storage, prev_name.to_ascii_lowercase());
+        }
+    }
+    if let Some(name) = display_name.as_ref() {
+        PROFILE_NAMES.save(deps.storage, name.to_ascii_lowercase(), &info.sender)?;
+    }
+    PROFILES.save(
+        deps.storage,
+        info.sender,
+        &Profile {
+            display_name,
+            bio,
+            avatar,
+            updated_ts: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("method", "set_profile"))
+}
+
+pub fn follow(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    addr: Addr,
+) -> Result<Response, ContractError> {
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+
+    let addr = deps.api.addr_validate(addr.as_str())?;
+    if addr == info.sender {
+        return Err(ContractError::CustomError {
+            val: "Cannot follow yourself".to_string(),
+        });
+    }
+    if FOLLOWING.has(deps.storage, (info.sender.clone(), addr.clone())) {
+        return Err(ContractError::CustomError {
+            val: "Already following".to_string(),
+        });
+    }
+    let ts = env.block.time.seconds();
+    FOLLOWING.save(deps.storage, (info.sender.clone(), addr.clone()), &ts)?;
+    FOLLOWERS.save(deps.storage, (addr.clone(), info.sender.clone()), &ts)?;
+    update_follow_counts(deps.storage, &info.sender, &addr, true)?;
+
+    Ok(Response::new().add_attribute("method", "follow"))
+}
 
-    let mut all_ath = NFT_RATE_ATH
-        .range(deps.storage, None, None, Order::Descending)
-        .map(|res| res.unwrap())
-        .collect::<Vec<(String, RateCount)>>();
-    let mut all_atl = NFT_RATE_ATL
-        .range(deps.storage, None, None, Order::Descending)
-        .map(|res| res.unwrap())
-        .collect::<Vec<(String, RateCount)>>();
+pub fn unfollow(deps: DepsMut, info: MessageInfo, addr: Addr) -> Result<Response, ContractError> {
+    let addr = deps.api.addr_validate(addr.as_str())?;
+    if !FOLLOWING.has(deps.storage, (info.sender.clone(), addr.clone())) {
+        return Err(ContractError::CustomError {
+            val: "Not following".to_string(),
+        });
+    }
+    FOLLOWING.remove(deps.storage, (info.sender.clone(), addr.clone()));
+    FOLLOWERS.remove(deps.storage, (addr.clone(), info.sender.clone()));
+    update_follow_counts(deps.storage, &info.sender, &addr, false)?;
 
-    // sort then truncate as needed
-    all_ath.sort_by(compare_desc);
-    if all_ath.len() > MAX_LEN_ALL_TIME {
-        for (k, _) in all_ath[MAX_LEN_ALL_TIME..].iter() {
-            NFT_RATE_ATH.remove(deps.storage, k.to_string());
+    Ok(Response::new().add_attribute("method", "unfollow"))
+}
+
+pub fn send_direct_message(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    to: Addr,
+    class_id: Option<String>,
+    content: Binary,
+) -> Result<Response, ContractError> {
+    check_not_paused(deps.storage, Action::DirectMessage)?;
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+
+    let to = deps.api.addr_validate(to.as_str())?;
+    if to == info.sender {
+        return Err(ContractError::CustomError {
+            val: "Cannot message yourself".to_string(),
+        });
+    }
+    if DM_BLOCKS.has(deps.storage, (to.clone(), info.sender.clone())) {
+        return Err(ContractError::CustomError {
+            val: "Recipient has blocked you".to_string(),
+        });
+    }
+    if content.is_empty() || content.len() > MAX_LEN_DIRECT_MESSAGE {
+        return Err(ContractError::CustomError {
+            val: "Invalid message content".to_string(),
+        });
+    }
+    if let Some(class_id) = class_id.clone() {
+        if load_visible_nft(deps.storage, class_id)?.is_none() {
+            return Err(ContractError::CustomError {
+                val: "NFT doesnt exist, cannot message".to_string(),
+            });
         }
     }
-    all_atl.sort_by(compare_asc);
-    if all_ath.len() > MAX_LEN_ALL_TIME {
-        for (k, _) in all_ath[MAX_LEN_ALL_TIME..].iter() {
-            NFT_RATE_ATL.remove(deps.storage, k.to_string());
+    let c = CONFIG.load(deps.storage)?;
+    let user_stats = USER_STATS.may_load(deps.storage, info.sender.clone())?;
+    if let Some(user_stats) = user_stats {
+        if c.unlock_dms.unwrap_or(DEFAULT_UNLOCK_DMS) > user_stats.ratings {
+            return Err(ContractError::CustomError {
+                val: "Not enough ratings, cannot send direct messages".to_string(),
+            });
         }
+    } else {
+        return Err(ContractError::CustomError {
+            val: "No user prefs, cannot send direct messages".to_string(),
+        });
     }
 
-    // sort days then truncate as needed
-    NFT_RATE_DAY_ATH.update(
+    let ts = env.block.time.seconds();
+    let id = DIRECT_MESSAGE_SEQ
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1;
+    DIRECT_MESSAGE_SEQ.save(deps.storage, &id)?;
+    DIRECT_MESSAGES.save(
         deps.storage,
-        last_day_ts,
-        |rates| -> Result<_, ContractError> {
-            match rates {
-                Some(s) => {
-                    let mut days: Vec<_> = s.into_iter().collect();
-                    days.sort_by(compare_desc);
-                    if days.len() > MAX_LEN_DAY {
-                        days.truncate(MAX_LEN_DAY + 1);
-                    }
-                    let days_map: HashMap<String, RateCount> = days.into_iter().collect();
-                    Ok(days_map)
-                }
-                None => {
-                    let mut n = HashMap::new();
-                    n.insert(
-                        class_id.clone(),
-                        RateCount {
-                            ts,
-                            sum: v as u64,
-                            total: 1,
-                        },
-                    );
-                    Ok(n)
-                }
-            }
+        (conversation_key(&info.sender, &to, &class_id), id),
+        &DirectMessage {
+            id: Some(id),
+            ts,
+            from: info.sender.clone(),
+            to: to.clone(),
+            class_id: class_id.clone(),
+            content,
         },
     )?;
-    NFT_RATE_DAY_ATL.update(
-        deps.storage,
-        last_day_ts,
-        |rates| -> Result<_, ContractError> {
-            match rates {
-                Some(s) => {
-                    let mut days: Vec<_> = s.into_iter().collect();
-                    days.sort_by(compare_asc);
-                    if days.len() > MAX_LEN_DAY {
-                        days.truncate(MAX_LEN_DAY + 1);
-                    }
-                    let days_map: HashMap<String, RateCount> = days.into_iter().collect();
-                    Ok(days_map)
-                }
-                None => {
-                    let mut n = HashMap::new();
-                    n.insert(
-                        class_id.clone(),
-                        RateCount {
-                            ts,
-                            sum: v as u64,
-                            total: 1,
-                        },
-                    );
-                    Ok(n)
-                }
+
+    // both sides list the thread
+    for (user, with) in [(&info.sender, &to), (&to, &info.sender)].iter() {
+        let key = (
+            (*user).clone(),
+            (*with).clone(),
+            class_id.clone().unwrap_or_default(),
+        );
+        let mut convo = USER_CONVERSATIONS
+            .may_load(deps.storage, key.clone())?
+            .unwrap_or(Conversation {
+                with: (*with).clone(),
+                class_id: class_id.clone(),
+                last_ts: ts,
+                count: 0,
+            });
+        convo.last_ts = ts;
+        convo.count = convo.count.saturating_add(1);
+        USER_CONVERSATIONS.save(deps.storage, key, &convo)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "send_direct_message")
+        .add_attribute("to", to))
+}
+
+pub fn block_user(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    addr: Addr,
+) -> Result<Response, ContractError> {
+    let addr = deps.api.addr_validate(addr.as_str())?;
+    if addr == info.sender {
+        return Err(ContractError::CustomError {
+            val: "Cannot block yourself".to_string(),
+        });
+    }
+    DM_BLOCKS.save(deps.storage, (info.sender, addr), &env.block.time.seconds())?;
+
+    Ok(Response::new().add_attribute("method", "block_user"))
+}
+
+pub fn unblock_user(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: Addr,
+) -> Result<Response, ContractError> {
+    let addr = deps.api.addr_validate(addr.as_str())?;
+    DM_BLOCKS.remove(deps.storage, (info.sender, addr));
+
+    Ok(Response::new().add_attribute("method", "unblock_user"))
+}
+
+pub fn mark_notifications_read(
+    deps: DepsMut,
+    info: MessageInfo,
+    up_to: u64,
+) -> Result<Response, ContractError> {
+    // read marker only moves forward, & never past the newest notification
+    let up_to = up_to.min(NOTIFICATION_SEQ.may_load(deps.storage)?.unwrap_or_default());
+    let last_read = NOTIFICATIONS_READ
+        .may_load(deps.storage, info.sender.clone())?
+        .unwrap_or_default();
+    if up_to > last_read {
+        NOTIFICATIONS_READ.save(deps.storage, info.sender, &up_to)?;
+    }
+
+    Ok(Response::new().add_attribute("method", "mark_notifications_read"))
+}
+
+pub fn react(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: MessageId,
+    reaction: String,
+) -> Result<Response, ContractError> {
+    check_not_paused(deps.storage, Action::Message)?;
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+
+    // only the configured set, so Message.reactions stays small
+    let c = CONFIG.load(deps.storage)?;
+    let allowed = match &c.reactions {
+        Some(reactions) => reactions.contains(&reaction),
+        None => DEFAULT_REACTIONS.contains(&reaction.as_str()),
+    };
+    if !allowed {
+        return Err(ContractError::CustomError {
+            val: "Invalid reaction".to_string(),
+        });
+    }
+    let mut msg = match MESSAGES.may_load(deps.storage, id)? {
+        Some(msg) => msg,
+        None => {
+            return Err(ContractError::CustomError {
+                val: "Message doesnt exist, cannot react".to_string(),
+            })
+        }
+    };
+    let reaction_key = (id, reaction.clone(), info.sender);
+    if MESSAGE_REACTIONS.has(deps.storage, reaction_key.clone()) {
+        return Err(ContractError::CustomError {
+            val: "Already reacted with this".to_string(),
+        });
+    }
+    MESSAGE_REACTIONS.save(deps.storage, reaction_key, &env.block.time.seconds())?;
+
+    let mut reactions = msg.reactions.unwrap_or_default();
+    match reactions.iter_mut().find(|r| r.reaction == reaction) {
+        Some(r) => r.count = r.count.saturating_add(1),
+        None => reactions.push(ReactionCount {
+            reaction: reaction.clone(),
+            count: 1,
+        }),
+    }
+    msg.reactions = Some(reactions);
+    MESSAGES.save(deps.storage, id, &msg)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "react")
+        .add_attribute("reaction", reaction))
+}
+
+pub fn unreact(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: MessageId,
+    reaction: String,
+) -> Result<Response, ContractError> {
+    let reaction_key = (id, reaction.clone(), info.sender);
+    if !MESSAGE_REACTIONS.has(deps.storage, reaction_key.clone()) {
+        return Err(ContractError::CustomError {
+            val: "No reaction to remove".to_string(),
+        });
+    }
+    MESSAGE_REACTIONS.remove(deps.storage, reaction_key);
+
+    if let Some(mut msg) = MESSAGES.may_load(deps.storage, id)? {
+        let mut reactions = msg.reactions.unwrap_or_default();
+        for r in reactions.iter_mut() {
+            if r.reaction == reaction {
+                r.count = r.count.saturating_sub(1);
             }
-        },
-    )?;
+        }
+        reactions.retain(|r| r.count > 0);
+        msg.reactions = if reactions.is_empty() {
+            None
+        } else {
+            Some(reactions)
+        };
+        MESSAGES.save(deps.storage, id, &msg)?;
+    }
 
-    // update user stats
-    USER_STATS.update(
+    Ok(Response::new()
+        .add_attribute("method", "unreact")
+        .add_attribute("reaction", reaction))
+}
+
+pub fn remove_message(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can do withdraw
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // Get by index
+    if delete_message(deps.storage, id)?.is_some() {
+        // update stats
+        STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
+            stats.messages = stats.messages.saturating_sub(1);
+            Ok(stats)
+        })?;
+    }
+
+    Ok(Response::new().add_attribute("method", "remove_message"))
+}
+
+pub fn report_message(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    reason: String,
+) -> Result<Response, ContractError> {
+    check_not_banned(deps.storage, &info.sender, env.block.time.seconds())?;
+    if !MESSAGES.has(deps.storage, id) {
+        return Err(ContractError::CustomError {
+            val: "Message doesnt exist, cannot report".to_string(),
+        });
+    }
+    // check reason length
+    if reason.chars().count() > MAX_LEN_MESSAGE {
+        return Err(ContractError::CustomError {
+            val: "Reason too long".to_string(),
+        });
+    }
+    let c = CONFIG.load(deps.storage)?;
+    // Same gate as posting, so fresh accounts cant brigade
+    let user_stats = USER_STATS.may_load(deps.storage, info.sender.clone())?;
+    if let Some(user_stats) = user_stats {
+        if !has_unlocked(
+            &user_stats,
+            c.unlock_messages.unwrap_or(5),
+            c.rep_unlock_messages,
+        ) {
+            return Err(ContractError::CustomError {
+                val: "Not enough ratings, cannot report".to_string(),
+            });
+        }
+    } else {
+        return Err(ContractError::CustomError {
+            val: "No user prefs, cannot report".to_string(),
+        });
+    }
+
+    let report_key = (id, info.sender);
+    if MESSAGE_REPORTS.has(deps.storage, report_key.clone()) {
+        return Err(ContractError::CustomError {
+            val: "Already reported this message".to_string(),
+        });
+    }
+    MESSAGE_REPORTS.save(
         deps.storage,
-        info.sender,
-        |stats| -> Result<_, ContractError> {
-            match stats {
-                Some(s) => {
-                    let mut st = s;
-                    st.last_rate_ts = ts;
-                    st.ratings = st.ratings.saturating_add(1);
-                    Ok(st)
-                }
-                None => Ok(UserStats {
-                    last_rate_ts: ts,
-                    ratings: 1,
-                    saves: 0,
-                    shares: 0,
-                }),
-            }
+        report_key,
+        &Report {
+            ts: env.block.time.seconds(),
+            reason,
         },
     )?;
+    let reports =
+        MESSAGE_REPORT_COUNTS.update(deps.storage, id, |count| -> Result<_, ContractError> {
+            Ok(count.unwrap_or_default().saturating_add(1))
+        })?;
+
+    Ok(Response::new()
+        .add_attribute("method", "report_message")
+        .add_attribute("reports", reports.to_string()))
+}
+
+// Owner decision on a reported message: remove it, or clear its reports so it shows again
+pub fn review_message(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+    remove: bool,
+) -> Result<Response, ContractError> {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can review
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if remove {
+        if delete_message(deps.storage, id)?.is_some() {
+            STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
+                stats.messages = stats.messages.saturating_sub(1);
+                Ok(stats)
+            })?;
+        }
+    } else {
+        // reporters keep their record, so they cannot report the same message twice
+        MESSAGE_REPORT_COUNTS.remove(deps.storage, id);
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "review_message")
+        .add_attribute("removed", remove.to_string()))
+}
+
+// Allow or deny a collection contract for sharing, None clears it
+pub fn set_collection_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract_addr: String,
+    status: Option<CollectionStatus>,
+) -> Result<Response, ContractError> {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can manage lists
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let contract_addr = list_key(&contract_addr);
+    match status {
+        Some(status) => COLLECTIONS.save(deps.storage, contract_addr.clone(), &status)?,
+        None => COLLECTIONS.remove(deps.storage, contract_addr.clone()),
+    }
 
-    // update stats
-    STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
-        stats.ratings = stats.ratings.saturating_add(1);
-        Ok(stats)
-    })?;
+    Ok(Response::new()
+        .add_attribute("method", "set_collection_status")
+        .add_attribute("contract_addr", contract_addr))
+}
+
+// Allow or deny a chain id for sharing, None clears it
+pub fn set_chain_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    chain_id: String,
+    status: Option<CollectionStatus>,
+) -> Result<Response, ContractError> {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can manage lists
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let chain_id = list_key(&chain_id);
+    match status {
+        Some(status) => CHAINS.save(deps.storage, chain_id.clone(), &status)?,
+        None => CHAINS.remove(deps.storage, chain_id.clone()),
+    }
 
-    Ok(Response::new().add_attribute("method", "rate"))
+    Ok(Response::new()
+        .add_attribute("method", "set_chain_status")
+        .add_attribute("chain_id", chain_id))
 }
 
-pub fn share(
+// Take down an NFT (scams, DMCA, etc), pulling it out of all ranked lists
+pub fn hide_nft(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     class_id: String,
-    token: TokenUri,
-    chain_id: Option<String>,
 ) -> Result<Response, ContractError> {
-    // check NFT doesnt exist
-    if NFTS.has(deps.storage, class_id.clone()) {
-        return Err(ContractError::CustomError {
-            val: "NFT already exists, cannot share again".to_string(),
-        });
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can hide
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
     }
-    let c = CONFIG.load(deps.storage)?;
+    let mut nft = match load_visible_nft(deps.storage, class_id.clone())? {
+        Some(nft) => nft,
+        None => {
+            return Err(ContractError::CustomError {
+                val: "NFT doesnt exist or already hidden".to_string(),
+            })
+        }
+    };
+    nft.hidden = Some(true);
+    NFTS.save(deps.storage, class_id.clone(), &nft)?;
 
-    // owner can share directly, otherwise check
-    if info.sender != c.owner {
-        let user_stats = USER_STATS.may_load(deps.storage, info.sender.clone())?;
-        if let Some(user_stats) = user_stats {
-            if c.unlock_share.unwrap_or(5) > user_stats.ratings {
-                return Err(ContractError::CustomError {
-                    val: "Not enough ratings, cannot share".to_string(),
-                });
-            }
+    // hand the rest of its slot to whatever is queued next
+    let now = env.block.time.seconds();
+    let slot = c.slot_duration.unwrap_or(DEFAULT_SLOT_DURATION);
+    advance_schedule(deps.storage, now, slot)?;
 
-            // Check max shares!!
-            if user_stats.shares > DEFAULT_USER_MAX_SHARES {
-                return Err(ContractError::CustomError {
-                    val: "Maximum allowed shares reached, cannot share".to_string(),
-                });
-            }
-        } else {
-            return Err(ContractError::CustomError {
-                val: "No user prefs, cannot share".to_string(),
-            });
+    // bids waiting to feature it won't ever show, give the funds back
+    let mut res = Response::new();
+    for (start, bid) in escrowed_bids(deps.storage, now)?.into_iter() {
+        if bid.class_id != class_id {
+            continue;
         }
+        BOOST_BIDS.remove(deps.storage, start);
+        res = res
+            .add_attribute("refunded", bid.bidder.to_string())
+            .add_submessage(SubMsg::new(BankMsg::Send {
+                to_address: bid.bidder.to_string(),
+                amount: vec![bid.amount],
+            }));
+    }
 
-        // check user provided adequate fee
-        if !has_coins(&info.funds, &c.share_fee) {
-            return Err(ContractError::CustomError {
-                val: "Invalid share fee amount, cannot share".to_string(),
-            });
+    NFT_RATE_ATH.remove(deps.storage, class_id.clone());
+    NFT_RATE_ATL.remove(deps.storage, class_id.clone());
+    NFT_SAVE_TOP.remove(deps.storage, class_id.clone());
+
+    // day buckets only go back a year, so a full pass is fine
+    for bucket in [NFT_RATE_DAY_ATH, NFT_RATE_DAY_ATL] {
+        let days = bucket
+            .range(deps.storage, None, None, Order::Ascending)
+            .filter_map(Result::ok)
+            .filter(|(_, rcs)| rcs.contains_key(&class_id))
+            .collect::<Vec<(u64, HashMap<String, RateCount>)>>();
+        for (day_ts, mut rcs) in days.into_iter() {
+            rcs.remove(&class_id);
+            bucket.save(deps.storage, day_ts, &rcs)?;
         }
     }
 
-    // create new NFT records
-    LIST.push_back(deps.storage, &class_id)?;
+    STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
+        stats.nfts = stats.nfts.saturating_sub(1);
+        Ok(stats)
+    })?;
 
-    let nft = Nft {
-        class_id: class_id.clone(),
-        token,
-        chain_id,
-        index: Some(u64::from(LIST.len(deps.storage)?)),
-    };
+    Ok(res
+        .add_attribute("method", "hide_nft")
+        .add_attribute("class_id", class_id))
+}
 
-    NFTS.save(deps.storage, class_id, &nft)?;
+// Restore a hidden NFT, it re-enters the all time lists with its existing counts
+pub fn unhide_nft(
+    deps: DepsMut,
+    info: MessageInfo,
+    class_id: String,
+) -> Result<Response, ContractError> {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can unhide
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut nft = match NFTS.may_load(deps.storage, class_id.clone())? {
+        Some(nft) if !is_visible(&nft) => nft,
+        _ => {
+            return Err(ContractError::CustomError {
+                val: "NFT doesnt exist or not hidden".to_string(),
+            })
+        }
+    };
+    nft.hidden = None;
+    NFTS.save(deps.storage, class_id.clone(), &nft)?;
 
-    // update user stats
-    USER_STATS.update(
-        deps.storage,
-        info.sender,
-        |stats| -> Result<_, ContractError> {
-            match stats {
-                Some(s) => {
-                    let mut st = s;
-                    st.shares = st.shares.saturating_add(1);
-                    Ok(st)
-                }
-                None => Ok(UserStats {
-                    last_rate_ts: env.block.time.seconds(),
-                    ratings: 0,
-                    saves: 0,
-                    shares: 1,
-                }),
-            }
-        },
-    )?;
+    // lists get truncated again on the next rating
+    if let Some(rc) = NFT_RATE_COUNTS.may_load(deps.storage, class_id.clone())? {
+        if rc.all.total > 0 {
+            NFT_RATE_ATH.save(deps.storage, class_id.clone(), &rc.all)?;
+            NFT_RATE_ATL.save(deps.storage, class_id.clone(), &rc.all)?;
+        }
+    }
+    let saves = NFT_SAVE_COUNTS
+        .may_load(deps.storage, class_id.clone())?
+        .unwrap_or_default();
+    update_most_saved(deps.storage, class_id.clone(), saves)?;
 
-    // update stats
     STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
         stats.nfts = stats.nfts.saturating_add(1);
         Ok(stats)
     })?;
 
-    Ok(Response::new().add_attribute("method", "share"))
+    Ok(Response::new()
+        .add_attribute("method", "unhide_nft")
+        .add_attribute("class_id", class_id))
 }
 
-pub fn save(
+// Withdraw ALL funds to specified recipient (like a DAO)
+pub fn withdraw(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    class_id: String,
+    receiver: Addr,
 ) -> Result<Response, ContractError> {
-    // check NFT doesnt exist
-    if !NFTS.has(deps.storage, class_id.clone()) {
-        return Err(ContractError::CustomError {
-            val: "NFT doesnt exist, cannot save".to_string(),
-        });
-    }
-    let c = CONFIG.load(deps.storage)?;
-    // check user provided adequate fee
-    if info.sender != c.owner && !has_coins(&info.funds, &c.save_fee) {
-        return Err(ContractError::CustomError {
-            val: "Invalid save fee amount, cannot save".to_string(),
-        });
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can do withdraw
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
     }
+    let mut balances = deps.querier.query_all_balances(env.contract.address)?;
 
-    let saved_list = USER_SAVED.may_load(deps.storage, info.sender.clone())?;
-
-    match saved_list {
-        Some(saved) => {
-            // remove any matching class_ids
-            let mut sl = saved;
-            if !sl.contains(&class_id) {
-                sl.push(class_id);
-                USER_SAVED.save(deps.storage, info.sender.clone(), &sl)?;
+    // hold back boost bids still in escrow, they may need refunding
+    for (_, bid) in escrowed_bids(deps.storage, env.block.time.seconds())?.into_iter() {
+        for balance in balances.iter_mut() {
+            if balance.denom == bid.amount.denom {
+                balance.amount = balance.amount.saturating_sub(bid.amount.amount);
             }
         }
-        None => {
-            USER_SAVED.save(deps.storage, info.sender.clone(), &vec![class_id])?;
-        }
     }
+    balances.retain(|balance| !balance.amount.is_zero());
+    let receiver = deps.api.addr_validate(receiver.as_str())?;
 
-    // update user stats
-    USER_STATS.update(
+    // everything may be held back, an empty send would fail
+    let mut res = Response::new().add_attribute("method", "withdraw");
+    if !balances.is_empty() {
+        res = res.add_submessage(SubMsg::new(BankMsg::Send {
+            to_address: receiver.to_string(),
+            amount: balances,
+        }));
+    }
+    Ok(res)
+}
+
+// Ban an address, optionally purging all their messages & ratings
+pub fn ban_user(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    addr: Addr,
+    purge: Option<bool>,
+) -> Result<Response, ContractError> {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can ban
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let addr = deps.api.addr_validate(addr.as_str())?;
+
+    USER_BANS.save(
         deps.storage,
-        info.sender,
-        |stats| -> Result<_, ContractError> {
-            match stats {
-                Some(s) => {
-                    let mut st = s;
-                    st.saves = st.saves.saturating_add(1);
-                    Ok(st)
-                }
-                None => Ok(UserStats {
-                    last_rate_ts: env.block.time.seconds(),
-                    ratings: 0,
-                    saves: 1,
-                    shares: 0,
-                }),
-            }
+        addr.clone(),
+        &UserBan {
+            ts: env.block.time.seconds(),
+            until: None,
         },
     )?;
 
-    // update total stats
-    STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
-        stats.saves = stats.saves.saturating_add(1);
-        Ok(stats)
-    })?;
+    let mut res = Response::new()
+        .add_attribute("method", "ban_user")
+        .add_attribute("addr", addr.clone());
+    if purge.unwrap_or(false) {
+        PURGES.save(deps.storage, addr.clone(), &PurgeCursor::default())?;
+        let (purged_messages, purged_ratings, done) =
+            purge_batch(deps.storage, &addr, DEFAULT_LIMIT as usize)?;
+        res = res
+            .add_attribute("purged_messages", purged_messages.to_string())
+            .add_attribute("purged_ratings", purged_ratings.to_string())
+            .add_attribute("done", done.to_string());
+    }
 
-    Ok(Response::new().add_attribute("method", "save"))
+    Ok(res)
 }
 
-pub fn unsave(
+// Next batch of a purge started by ban_user
+pub fn purge_user(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
-    class_id: String,
+    addr: Addr,
+    limit: Option<u32>,
 ) -> Result<Response, ContractError> {
-    // check NFT exists
-    if !NFTS.has(deps.storage, class_id.clone()) {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can purge
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    let addr = deps.api.addr_validate(addr.as_str())?;
+    if !PURGES.has(deps.storage, addr.clone()) {
         return Err(ContractError::CustomError {
-            val: "NFT doesnt exist, cannot unsave".to_string(),
+            val: "No purge running for this address".to_string(),
         });
     }
-    let saved_list = USER_SAVED.may_load(deps.storage, info.sender.clone())?;
-
-    if let Some(saved_list) = saved_list {
-        // remove any matching class_ids
-        let mut sl = saved_list;
-        sl.retain(|x| x.clone() != class_id);
-        USER_SAVED.save(deps.storage, info.sender.clone(), &sl)?;
-
-        // update user stats
-        USER_STATS.update(
-            deps.storage,
-            info.sender,
-            |stats| -> Result<_, ContractError> {
-                match stats {
-                    Some(s) => {
-                        let mut st = s;
-                        if st.saves > 0 {
-                            st.saves = st.saves.saturating_sub(1);
-                        }
-                        Ok(st)
-                    }
-                    None => Ok(UserStats {
-                        last_rate_ts: env.block.time.seconds(),
-                        ratings: 0,
-                        saves: 0,
-                        shares: 0,
-                    }),
-                }
-            },
-        )?;
-
-        // update total stats
-        STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
-            stats.saves = stats.saves.saturating_sub(1);
-            Ok(stats)
-        })?;
-    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let (purged_messages, purged_ratings, done) = purge_batch(deps.storage, &addr, limit)?;
 
-    Ok(Response::new().add_attribute("method", "unsave"))
+    Ok(Response::new()
+        .add_attribute("method", "purge_user")
+        .add_attribute("addr", addr)
+        .add_attribute("purged_messages", purged_messages.to_string())
+        .add_attribute("purged_ratings", purged_ratings.to_string())
+        .add_attribute("done", done.to_string()))
 }
 
-pub fn send_message(
+// Mute an address until a given timestamp (seconds)
+pub fn mute_user(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    class_id: String,
-    message: String,
-    meta: Option<Binary>,
+    addr: Addr,
+    until: u64,
 ) -> Result<Response, ContractError> {
-    // check NFT exists
-    if !NFTS.has(deps.storage, class_id.clone()) {
-        return Err(ContractError::CustomError {
-            val: "NFT doesnt exist, cannot message".to_string(),
-        });
-    }
-    // check message length
-    if message.len() > MAX_LEN_MESSAGE {
-        return Err(ContractError::CustomError {
-            val: "Message too long".to_string(),
-        });
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can mute
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
     }
-    let c = CONFIG.load(deps.storage)?;
-    // Get the prefs of receiver, to filter out thangs
-    let user_stats = USER_STATS.may_load(deps.storage, info.sender.clone())?;
-    if let Some(user_stats) = user_stats {
-        if c.unlock_messages.unwrap_or(5) > user_stats.ratings {
-            return Err(ContractError::CustomError {
-                val: "Not enough ratings, cannot post".to_string(),
-            });
-        }
-    } else {
-        return Err(ContractError::CustomError {
-            val: "No user prefs, cannot post".to_string(),
-        });
+    let addr = deps.api.addr_validate(addr.as_str())?;
+
+    USER_BANS.save(
+        deps.storage,
+        addr.clone(),
+        &UserBan {
+            ts: env.block.time.seconds(),
+            until: Some(until),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("method", "mute_user")
+        .add_attribute("addr", addr)
+        .add_attribute("until", until.to_string()))
+}
+
+// Lift a ban or mute
+pub fn unban_user(deps: DepsMut, info: MessageInfo, addr: Addr) -> Result<Response, ContractError> {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can unban
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
     }
+    let addr = deps.api.addr_validate(addr.as_str())?;
+    USER_BANS.remove(deps.storage, addr.clone());
 
-    let msg_id = env.block.time.seconds();
-    let new_msg = Message {
-        ts: msg_id,
-        class_id: class_id.clone(),
-        message,
-        from: info.sender,
-        meta,
-    };
+    Ok(Response::new()
+        .add_attribute("method", "unban_user")
+        .add_attribute("addr", addr))
+}
 
-    // Get last index, push into new
-    let stored_ids = MESSAGES_IDS.may_load(deps.storage, class_id.clone())?;
-    let mut prev_ids = stored_ids.unwrap_or(vec![]);
-    prev_ids.push(msg_id);
-    MESSAGES.save(deps.storage, msg_id, &new_msg)?;
-    MESSAGES_IDS.save(deps.storage, class_id, &prev_ids)?;
+// Pause or unpause a single user action, for when spam shows up
+pub fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    action: Action,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let c: Config = CONFIG.load(deps.storage)?;
+    // Only owner can pause
+    if info.sender != c.owner {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    // update stats
-    STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
-        stats.messages = stats.messages.saturating_add(1);
-        Ok(stats)
-    })?;
+    let mut actions = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    actions.retain(|a| a != &action);
+    if paused {
+        actions.push(action);
+    }
+    PAUSED.save(deps.storage, &actions)?;
 
-    Ok(Response::new().add_attribute("method", "message"))
+    Ok(Response::new()
+        .add_attribute("method", "set_paused")
+        .add_attribute("paused", paused.to_string()))
 }
 
-pub fn remove_message(
+pub fn seed_leaderboards(
     deps: DepsMut,
     info: MessageInfo,
-    id: u64,
+    limit: Option<u32>,
 ) -> Result<Response, ContractError> {
     let c: Config = CONFIG.load(deps.storage)?;
-    // Only owner can do withdraw
+    // Only owner can run the seeding batches
     if info.sender != c.owner {
         return Err(ContractError::Unauthorized {});
     }
+    let mut seed = LEADERBOARD_SEED
+        .may_load(deps.storage)?
+        .ok_or(ContractError::CustomError {
+            val: "Leaderboards already seeded".to_string(),
+        })?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    // ratings & shares are set from the user's stats, so live updates in between don't matter
+    let mut processed: usize = 0;
+    if !seed.users_done {
+        let start = seed.users_after.clone().map(Bound::exclusive);
+        let users = USER_STATS
+            .range(deps.storage, start, None, Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<(Addr, UserStats)>>>()?;
+        processed = users.len();
+        seed.users_done = users.len() < limit;
+        for (addr, st) in users.into_iter() {
+            set_user_score(
+                deps.storage,
+                UserMetric::Ratings.as_str(),
+                &addr,
+                st.ratings,
+            )?;
+            set_user_score(deps.storage, UserMetric::Shares.as_str(), &addr, st.shares)?;
+            seed.users_after = Some(addr);
+        }
+    }
 
-    // Get by index
-    let msg = MESSAGES.may_load(deps.storage, id)?;
-    MESSAGES.remove(deps.storage, id);
-    if let Some(msg) = msg {
-        let msgs = MESSAGES_IDS.may_load(deps.storage, msg.class_id.clone())?;
-        if let Some(msgs) = msgs {
-            // remove any matching msg ids
-            let mut ms = msgs;
-            ms.retain(|&x| x != id);
-            MESSAGES_IDS.save(deps.storage, msg.class_id, &ms)?;
+    // messages are added on top of what was counted since the migrate
+    let mut done = false;
+    if seed.users_done && processed < limit {
+        let remaining = limit - processed;
+        let start = seed.messages_after.map(Bound::exclusive);
+        let end = Some(Bound::inclusive(seed.messages_until));
+        let msgs = MESSAGES
+            .range(deps.storage, start, end, Order::Ascending)
+            .take(remaining)
+            .collect::<StdResult<Vec<(u64, Message)>>>()?;
+        done = msgs.len() < remaining;
+        let mut user_messages: HashMap<Addr, u64> = HashMap::new();
+        for (id, msg) in msgs.into_iter() {
+            *user_messages.entry(msg.from).or_default() += 1;
+            seed.messages_after = Some(id);
+        }
+        let metric = UserMetric::Messages.as_str();
+        for (addr, count) in user_messages.into_iter() {
+            let prev = USER_SCORES
+                .may_load(deps.storage, (metric.to_string(), addr.clone()))?
+                .unwrap_or_default();
+            set_user_score(deps.storage, metric, &addr, prev.saturating_add(count))?;
         }
     }
 
-    // update stats
-    STATS.update(deps.storage, |mut stats| -> Result<_, ContractError> {
-        stats.messages = stats.messages.saturating_sub(1);
-        Ok(stats)
-    })?;
+    if done {
+        LEADERBOARD_SEED.remove(deps.storage);
+    } else {
+        LEADERBOARD_SEED.save(deps.storage, &seed)?;
+    }
 
-    Ok(Response::new().add_attribute("method", "remove_message"))
+    Ok(Response::new()
+        .add_attribute("method", "seed_leaderboards")
+        .add_attribute("done", done.to_string()))
 }
 
-// Withdraw ALL funds to specified recipient (like a DAO)
-pub fn withdraw(
+pub fn set_banned_words(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
-    receiver: Addr,
+    add: Vec<String>,
+    remove: Vec<String>,
 ) -> Result<Response, ContractError> {
     let c: Config = CONFIG.load(deps.storage)?;
-    // Only owner can do withdraw
+    // Only owner can manage the word list
     if info.sender != c.owner {
         return Err(ContractError::Unauthorized {});
     }
-    let balances = deps.querier.query_all_balances(env.contract.address)?;
-    let transfer = SubMsg::new(BankMsg::Send {
-        to_address: deps.api.addr_validate(receiver.as_str())?.to_string(),
-        amount: balances,
-    });
 
-    Ok(Response::new()
-        .add_attribute("method", "withdraw")
-        .add_submessage(transfer))
+    for word in remove.into_iter() {
+        BANNED_WORDS.remove(deps.storage, word.trim().to_lowercase());
+    }
+    for word in add.into_iter() {
+        let word = word.trim().to_lowercase();
+        if word.is_empty()
+            || word.chars().count() > MAX_LEN_BANNED_WORD
+            || word.chars().any(|ch| !ch.is_alphanumeric())
+        {
+            return Err(ContractError::CustomError {
+                val: format!("Invalid banned word: {}", word),
+            });
+        }
+        BANNED_WORDS.save(deps.storage, word, &Empty {})?;
+    }
+
+    Ok(Response::new().add_attribute("method", "set_banned_words"))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::GetList { kind, sort } => to_binary(&query_ranked_list(deps, env, kind, sort)?),
-        QueryMsg::GetCurrentNft {} => to_binary(&query_current_nft(deps)?),
-        QueryMsg::GetNftByIndex { index } => to_binary(&query_nft_by_index(deps, index)?),
+        QueryMsg::GetList { kind, sort } => {
+            to_json_binary(&query_ranked_list(deps, env, kind, sort)?)
+        }
+        QueryMsg::GetCurrentNft {} => to_json_binary(&query_current_nft(deps, env)?),
+        QueryMsg::GetBoost {} => to_json_binary(&query_boost(deps, env, false)?),
+        QueryMsg::GetBoostAuction {} => to_json_binary(&query_boost(deps, env, true)?),
+        QueryMsg::GetSchedule { limit } => to_json_binary(&query_schedule(deps, env, limit)?),
+        QueryMsg::GetNftByIndex { index } => to_json_binary(&query_nft_by_index(deps, index)?),
+        QueryMsg::GetNfts {
+            start_index,
+            limit,
+            order,
+            include_rates,
+        } => to_json_binary(&query_nfts(deps, start_index, limit, order, include_rates)?),
         QueryMsg::GetNftByClassId { class_id } => {
-            to_binary(&query_nft_by_class_id(deps, class_id)?)
+            to_json_binary(&query_nft_by_class_id(deps, class_id)?)
+        }
+        QueryMsg::GetUserNftSaved {
+            addr,
+            start_after,
+            limit,
+            viewer,
+        } => to_json_binary(&query_user_saved_nfts(
+            deps,
+            addr,
+            start_after,
+            limit,
+            viewer,
+        )?),
+        QueryMsg::GetNftSavers {
+            class_id,
+            start_after,
+            limit,
+            viewer,
+        } => to_json_binary(&query_nft_savers(
+            deps,
+            class_id,
+            start_after,
+            limit,
+            viewer,
+        )?),
+        QueryMsg::GetNftSaveCount { class_id } => {
+            to_json_binary(&query_nft_save_count(deps, class_id)?)
+        }
+        QueryMsg::GetUserCollections {
+            addr,
+            start_after,
+            limit,
+            viewer,
+        } => to_json_binary(&query_user_collections(
+            deps,
+            addr,
+            start_after,
+            limit,
+            viewer,
+        )?),
+        QueryMsg::GetCollectionNfts {
+            addr,
+            collection,
+            start_after,
+            limit,
+            viewer,
+        } => to_json_binary(&query_collection_nfts(
+            deps,
+            addr,
+            collection,
+            start_after,
+            limit,
+            viewer,
+        )?),
+        QueryMsg::GetSavedVisibility { addr } => {
+            to_json_binary(&query_saved_visibility(deps, addr)?)
+        }
+        QueryMsg::GetGallery { owner, name } => to_json_binary(&query_gallery(deps, owner, name)?),
+        QueryMsg::GetGalleries { start_after, limit } => {
+            to_json_binary(&query_galleries(deps, start_after, limit)?)
+        }
+        QueryMsg::GetUserSavedGalleries {
+            addr,
+            start_after,
+            limit,
+        } => to_json_binary(&query_user_saved_galleries(deps, addr, start_after, limit)?),
+        QueryMsg::GetNftRate { class_id } => to_json_binary(&query_nft_rate(deps, class_id)?),
+        QueryMsg::GetAllMessages {
+            start_after,
+            limit,
+            order,
+            include_profiles,
+        } => to_json_binary(&with_profiles(
+            deps,
+            query_all_messages(deps, start_after, limit, order)?,
+            include_profiles,
+        )?),
+        QueryMsg::GetNftMessages {
+            class_id,
+            start_after,
+            limit,
+            order,
+            include_profiles,
+        } => to_json_binary(&with_profiles(
+            deps,
+            query_messages(deps, class_id, start_after, limit, order)?,
+            include_profiles,
+        )?),
+        QueryMsg::GetThread {
+            id,
+            start_after,
+            limit,
+            include_profiles,
+        } => to_json_binary(&with_profiles(
+            deps,
+            query_thread(deps, id, start_after, limit)?,
+            include_profiles,
+        )?),
+        QueryMsg::GetReportedMessages { start_after, limit } => {
+            to_json_binary(&query_reported_messages(deps, start_after, limit)?)
+        }
+        QueryMsg::GetNftRatings {
+            class_id,
+            start_after,
+            limit,
+            include_profiles,
+        } => to_json_binary(&query_nft_ratings(
+            deps,
+            class_id,
+            start_after,
+            limit,
+            include_profiles,
+        )?),
+        QueryMsg::GetUser { addr } => to_json_binary(&query_user(deps, env, addr)?),
+        QueryMsg::GetUserActivity {
+            addr,
+            start_after,
+            limit,
+        } => to_json_binary(&query_user_activity(deps, addr, start_after, limit)?),
+        QueryMsg::GetUserLeaderboard {
+            metric,
+            period,
+            start_after,
+            limit,
+        } => to_json_binary(&query_user_leaderboard(
+            deps,
+            env,
+            metric,
+            period,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::GetUserBadges { addr } => to_json_binary(&query_user_badges(deps, addr)?),
+        QueryMsg::GetProfile { addr } => to_json_binary(&query_profile(deps, addr)?),
+        QueryMsg::ResolveName { name } => to_json_binary(&query_resolve_name(deps, name)?),
+        QueryMsg::GetConfig {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::GetTotalStats {} => to_json_binary(&query_stats(deps)?),
+        QueryMsg::GetPaused {} => to_json_binary(&query_paused(deps)?),
+        QueryMsg::GetFollowers {
+            addr,
+            start_after,
+            limit,
+        } => to_json_binary(&query_followers(deps, addr, start_after, limit)?),
+        QueryMsg::GetFollowing {
+            addr,
+            start_after,
+            limit,
+        } => to_json_binary(&query_following(deps, addr, start_after, limit)?),
+        QueryMsg::GetFeed {
+            addr,
+            start_after,
+            limit,
+        } => to_json_binary(&query_feed(deps, addr, start_after, limit)?),
+        QueryMsg::GetConversation {
+            a,
+            b,
+            class_id,
+            start_after,
+            limit,
+            order,
+        } => to_json_binary(&query_conversation(
+            deps,
+            a,
+            b,
+            class_id,
+            start_after,
+            limit,
+            order,
+        )?),
+        QueryMsg::GetConversations {
+            addr,
+            start_after,
+            limit,
+        } => to_json_binary(&query_conversations(deps, addr, start_after, limit)?),
+        QueryMsg::GetBlockedUsers {
+            addr,
+            start_after,
+            limit,
+        } => to_json_binary(&query_blocked_users(deps, addr, start_after, limit)?),
+        QueryMsg::GetNotifications {
+            addr,
+            start_after,
+            limit,
+        } => to_json_binary(&query_notifications(deps, addr, start_after, limit)?),
+        QueryMsg::GetBannedWords { start_after, limit } => {
+            to_json_binary(&query_banned_words(deps, start_after, limit)?)
+        }
+        QueryMsg::GetCollections { start_after, limit } => {
+            to_json_binary(&query_collections(deps, start_after, limit)?)
+        }
+        QueryMsg::GetChains { start_after, limit } => {
+            to_json_binary(&query_chains(deps, start_after, limit)?)
         }
-        QueryMsg::GetUserNftSaved { addr } => to_binary(&query_user_saved_nfts(deps, addr)?),
-        QueryMsg::GetNftRate { class_id } => to_binary(&query_nft_rate(deps, class_id)?),
-        QueryMsg::GetAllMessages { from_index, limit } => {
-            to_binary(&query_all_messages(deps, from_index, limit)?)
+        QueryMsg::IsCollectionEligible {
+            contract_addr,
+            chain_id,
+        } => to_json_binary(&query_collection_eligible(deps, contract_addr, chain_id)?),
+        QueryMsg::GetBannedUsers { start_after, limit } => {
+            to_json_binary(&query_banned_users(deps, env, start_after, limit)?)
         }
-        QueryMsg::GetNftMessages { class_id } => to_binary(&query_messages(deps, class_id)?),
-        QueryMsg::GetUser { addr } => to_binary(&query_user(deps, addr)?),
-        QueryMsg::GetConfig {} => to_binary(&query_config(deps)?),
-        QueryMsg::GetTotalStats {} => to_binary(&query_stats(deps)?),
         QueryMsg::GetClassId {
             contract_addr,
             token_id,
-        } => to_binary(&query_class_id(contract_addr, token_id)?),
-        QueryMsg::UserHasSavedNft { addr, class_id } => {
-            to_binary(&query_user_saved_nft(deps, addr, class_id)?)
-        }
+        } => to_json_binary(&query_class_id(contract_addr, token_id)?),
+        QueryMsg::UserHasSavedNft {
+            addr,
+            class_id,
+            viewer,
+        } => to_json_binary(&query_user_saved_nft(deps, addr, class_id, viewer)?),
         QueryMsg::GetUserNftRate { addr, class_id } => {
-            to_binary(&query_user_rated_nft(deps, addr, class_id)?)
+            to_json_binary(&query_user_rated_nft(deps, addr, class_id)?)
         }
     }
 }
@@ -789,66 +3896,326 @@ fn query_ranked_list(
                     .collect(),
             }
         }
+        // sum holds the number of savers, so averages sort the same
+        ListKind::MostSaved => {
+            let mut saved = NFT_SAVE_TOP
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<(String, u64)>>>()?;
+            saved.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            if sort == ListSort::Lowest {
+                saved.reverse();
+            }
+            saved
+                .into_iter()
+                .map(|(class_id, saves)| {
+                    (
+                        class_id,
+                        RateCount {
+                            ts: 0,
+                            sum: saves,
+                            total: 1,
+                        },
+                    )
+                })
+                .collect()
+        }
     };
 
     for (key, rc) in keys.into_iter() {
-        let nft = NFTS.load(deps.storage, key)?;
-        list.push((nft, rc));
+        if let Some(nft) = load_visible_nft(deps.storage, key)? {
+            list.push((nft, rc));
+        }
     }
 
     Ok(list)
 }
 
-fn query_current_nft(deps: Deps) -> StdResult<Option<Nft>> {
-    let i = LIST.back(deps.storage)?;
-    if let Some(class_id) = i {
-        let r = NFTS.may_load(deps.storage, class_id)?;
-        Ok(r)
-    } else {
-        Ok(None)
+fn query_current_nft(deps: Deps, env: Env) -> StdResult<Option<Nft>> {
+    let c = CONFIG.load(deps.storage)?;
+    let slot = c.slot_duration.unwrap_or(DEFAULT_SLOT_DURATION);
+    let schedule = schedule_view(deps.storage, env.block.time.seconds(), slot, 1)?;
+    if let Some(cur) = schedule.into_iter().next() {
+        if cur.start <= env.block.time.seconds() {
+            return load_visible_nft(deps.storage, cur.class_id);
+        }
+    }
+
+    // the last scheduled NFT stays up until something replaces it
+    if let Some(cur) = SCHEDULE_CURRENT.may_load(deps.storage)? {
+        if let Some(nft) = load_visible_nft(deps.storage, cur.class_id)? {
+            return Ok(Some(nft));
+        }
     }
+
+    // hidden, or nothing scheduled yet (shared before the rotation existed), walk back from the latest until we find one still visible
+    for class_id in LIST.iter(deps.storage)?.rev() {
+        if let Some(nft) = load_visible_nft(deps.storage, class_id?)? {
+            return Ok(Some(nft));
+        }
+    }
+    Ok(None)
+}
+
+// Either the bid live right now, or the leading one for the window being auctioned
+fn query_boost(deps: Deps, env: Env, auction: bool) -> StdResult<Option<BoostBid>> {
+    let now = env.block.time.seconds();
+    let bid = if auction {
+        escrowed_bids(deps.storage, now)?
+            .into_iter()
+            .next()
+            .map(|(_, bid)| bid)
+    } else {
+        BOOST_BIDS
+            .range(
+                deps.storage,
+                None,
+                Some(Bound::inclusive(now)),
+                Order::Descending,
+            )
+            .next()
+            .transpose()?
+            .map(|(_, bid)| bid)
+            .filter(|bid| bid.end > now)
+    };
+    Ok(bid.filter(|b| {
+        load_visible_nft(deps.storage, b.class_id.clone())
+            .map(|nft| nft.is_some())
+            .unwrap_or(false)
+    }))
+}
+
+fn query_schedule(deps: Deps, env: Env, limit: Option<u32>) -> StdResult<Vec<ScheduledNft>> {
+    let c = CONFIG.load(deps.storage)?;
+    let slot = c.slot_duration.unwrap_or(DEFAULT_SLOT_DURATION);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    schedule_view(deps.storage, env.block.time.seconds(), slot, limit)
 }
 
 fn query_nft_by_index(deps: Deps, index: u32) -> StdResult<Option<Nft>> {
     let i = LIST.get(deps.storage, index)?;
     if let Some(class_id) = i {
-        let r = NFTS.may_load(deps.storage, class_id)?;
+        let r = load_visible_nft(deps.storage, class_id)?;
         Ok(r)
     } else {
         Ok(None)
     }
 }
 
+// Walks the queue from start_index in either direction, skipping hidden NFTs
+fn query_nfts(
+    deps: Deps,
+    start_index: Option<u32>,
+    limit: Option<u32>,
+    order: Option<ListOrder>,
+    include_rates: Option<bool>,
+) -> StdResult<Vec<NftInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let order = order.unwrap_or(ListOrder::Ascending);
+    let include_rates = include_rates.unwrap_or(false);
+    let len = LIST.len(deps.storage)?;
+    let mut nfts: Vec<NftInfo> = vec![];
+    if len == 0 {
+        return Ok(nfts);
+    }
+
+    let mut index = match order {
+        ListOrder::Ascending => start_index.unwrap_or(0),
+        ListOrder::Descending => start_index.unwrap_or(len - 1).min(len - 1),
+    };
+    while index < len && nfts.len() < limit {
+        if let Some(class_id) = LIST.get(deps.storage, index)? {
+            if let Some(nft) = load_visible_nft(deps.storage, class_id.clone())? {
+                let rates = if include_rates {
+                    NFT_RATE_COUNTS.may_load(deps.storage, class_id)?
+                } else {
+                    None
+                };
+                nfts.push(NftInfo { nft, rates });
+            }
+        }
+        index = match order {
+            ListOrder::Ascending => index + 1,
+            ListOrder::Descending => match index.checked_sub(1) {
+                Some(i) => i,
+                None => break,
+            },
+        };
+    }
+
+    Ok(nfts)
+}
+
 fn query_nft_by_class_id(deps: Deps, class_id: String) -> StdResult<Option<Nft>> {
     let r = NFTS.may_load(deps.storage, class_id)?;
     Ok(r)
 }
 
-fn query_user_saved_nfts(deps: Deps, addr: Addr) -> StdResult<Vec<Nft>> {
-    let saved = USER_SAVED.may_load(deps.storage, addr)?;
-    if let Some(saved) = saved {
-        let mut saved_nfts: Vec<Nft> = vec![];
+// Paged by class_id, an NFT saved in several collections shows up once
+fn query_user_saved_nfts(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    viewer: Option<Addr>,
+) -> StdResult<Vec<Nft>> {
+    assert_can_view_saved(deps.storage, &addr, viewer.as_ref())?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let mut saved_nfts: Vec<Nft> = vec![];
 
-        for class_id in saved.into_iter() {
-            let nft = NFTS.may_load(deps.storage, class_id)?;
-            if let Some(nft) = nft {
-                saved_nfts.push(nft);
-            }
+    for class_id in USER_SAVED_NFTS
+        .prefix(addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+    {
+        if saved_nfts.len() >= limit {
+            break;
+        }
+        if let Some(nft) = load_visible_nft(deps.storage, class_id?)? {
+            saved_nfts.push(nft);
         }
+    }
 
-        Ok(saved_nfts)
-    } else {
-        Ok(vec![])
+    Ok(saved_nfts)
+}
+
+fn query_user_saved_nft(
+    deps: Deps,
+    addr: Addr,
+    class_id: String,
+    viewer: Option<Addr>,
+) -> StdResult<bool> {
+    assert_can_view_saved(deps.storage, &addr, viewer.as_ref())?;
+    Ok(NFT_SAVERS.has(deps.storage, (class_id, addr)))
+}
+
+// Savers with a non-public saved list are left out, unless the viewer can see them
+fn query_nft_savers(
+    deps: Deps,
+    class_id: String,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    viewer: Option<Addr>,
+) -> StdResult<Vec<(Addr, u64)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let mut savers: Vec<(Addr, u64)> = vec![];
+
+    for res in NFT_SAVERS
+        .prefix(class_id)
+        .range(deps.storage, start, None, Order::Ascending)
+    {
+        if savers.len() >= limit {
+            break;
+        }
+        let (addr, ts) = res?;
+        if can_view_saved(deps.storage, &addr, viewer.as_ref())? {
+            savers.push((addr, ts));
+        }
     }
+
+    Ok(savers)
 }
 
-fn query_user_saved_nft(deps: Deps, addr: Addr, class_id: String) -> StdResult<bool> {
-    let saved = USER_SAVED.may_load(deps.storage, addr)?;
-    if let Some(saved) = saved {
-        Ok(saved.contains(&class_id))
-    } else {
-        Ok(false)
+fn query_nft_save_count(deps: Deps, class_id: String) -> StdResult<u64> {
+    let count = NFT_SAVE_COUNTS.may_load(deps.storage, class_id)?;
+    Ok(count.unwrap_or_default())
+}
+
+fn query_user_collections(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    viewer: Option<Addr>,
+) -> StdResult<Vec<SavedCollection>> {
+    assert_can_view_saved(deps.storage, &addr, viewer.as_ref())?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    SAVED_COLLECTIONS
+        .prefix(addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, sc)| sc))
+        .collect()
+}
+
+// Published galleries are always viewable, otherwise the owner's visibility applies
+fn query_collection_nfts(
+    deps: Deps,
+    addr: Addr,
+    collection: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+    viewer: Option<Addr>,
+) -> StdResult<Vec<SavedNft>> {
+    if !GALLERIES.has(deps.storage, (addr.clone(), collection.clone())) {
+        assert_can_view_saved(deps.storage, &addr, viewer.as_ref())?;
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let mut saved_nfts: Vec<SavedNft> = vec![];
+
+    for res in SAVED_NFTS
+        .prefix((addr, collection))
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+    {
+        let (class_id, meta) = res?;
+        if let Some(nft) = load_visible_nft(deps.storage, class_id)? {
+            saved_nfts.push(SavedNft { nft, meta });
+        }
+    }
+
+    Ok(saved_nfts)
+}
+
+fn query_saved_visibility(deps: Deps, addr: Addr) -> StdResult<Visibility> {
+    let v = SAVED_VISIBILITY.may_load(deps.storage, addr)?;
+    Ok(v.unwrap_or(Visibility::Public))
+}
+
+fn query_gallery(deps: Deps, owner: Addr, name: String) -> StdResult<Option<Gallery>> {
+    GALLERIES.may_load(deps.storage, (owner, name))
+}
+
+fn query_galleries(
+    deps: Deps,
+    start_after: Option<(Addr, String)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Gallery>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    GALLERIES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, g)| g))
+        .collect()
+}
+
+fn query_user_saved_galleries(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<(Addr, String)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Gallery>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let mut galleries: Vec<Gallery> = vec![];
+
+    for res in SAVED_GALLERIES
+        .sub_prefix(addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+    {
+        // unpublished galleries drop out
+        if let Some(g) = GALLERIES.may_load(deps.storage, res?)? {
+            galleries.push(g);
+        }
     }
+
+    Ok(galleries)
 }
 
 fn query_user_rated_nft(deps: Deps, addr: Addr, class_id: String) -> StdResult<Option<Rate>> {
@@ -862,42 +4229,300 @@ fn query_nft_rate(deps: Deps, class_id: String) -> StdResult<Option<RateCounts>>
 
 fn query_all_messages(
     deps: Deps,
-    from_index: Option<u64>,
-    limit: Option<u64>,
-) -> StdResult<Vec<Message>> {
-    let from_index = from_index.unwrap_or(0);
-    let limit = limit.unwrap_or(100);
+    start_after: Option<MessageId>,
+    limit: Option<u32>,
+    order: Option<ListOrder>,
+) -> StdResult<Vec<MessageResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let (min, max, order) = message_bounds(start_after, order.unwrap_or(ListOrder::Descending));
+    let threshold = report_threshold(deps)?;
+    let mut msgs: Vec<MessageResponse> = vec![];
 
-    Ok(MESSAGES
-        .range(deps.storage, None, None, Order::Descending)
-        .skip(from_index as usize)
-        .take(limit as usize)
-        .map(|res| res.unwrap().1)
-        .collect::<Vec<_>>())
+    for res in MESSAGES.range(deps.storage, min, max, order) {
+        if msgs.len() >= limit {
+            break;
+        }
+        let (id, msg) = res?;
+        if is_message_hidden(deps.storage, threshold, id)? {
+            continue;
+        }
+        msgs.push(message_view(id, msg));
+    }
+
+    Ok(msgs)
 }
 
-fn query_messages(deps: Deps, class_id: String) -> StdResult<Vec<Message>> {
-    let mids = MESSAGES_IDS.may_load(deps.storage, class_id)?;
+fn query_messages(
+    deps: Deps,
+    class_id: String,
+    start_after: Option<MessageId>,
+    limit: Option<u32>,
+    order: Option<ListOrder>,
+) -> StdResult<Vec<MessageResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let (min, max, order) = message_bounds(start_after, order.unwrap_or(ListOrder::Ascending));
+    let threshold = report_threshold(deps)?;
+    let mut msgs: Vec<MessageResponse> = vec![];
+
+    for res in NFT_MESSAGES
+        .prefix(class_id)
+        .keys(deps.storage, min, max, order)
+    {
+        if msgs.len() >= limit {
+            break;
+        }
+        let id = res?;
+        if is_message_hidden(deps.storage, threshold, id)? {
+            continue;
+        }
+        if let Some(msg) = MESSAGES.may_load(deps.storage, id)? {
+            msgs.push(message_view(id, msg));
+        }
+    }
+
+    Ok(msgs)
+}
 
-    if let Some(msg_ids) = mids {
-        let mut msgs: Vec<Message> = vec![];
+fn query_thread(
+    deps: Deps,
+    id: MessageId,
+    start_after: Option<MessageId>,
+    limit: Option<u32>,
+) -> StdResult<Vec<MessageResponse>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let threshold = report_threshold(deps)?;
+    let mut msgs: Vec<MessageResponse> = vec![];
 
-        for ts in msg_ids.into_iter() {
-            let msg = MESSAGES.may_load(deps.storage, ts)?;
-            if let Some(msg) = msg {
-                msgs.push(msg);
-            }
+    for res in MESSAGE_REPLIES
+        .prefix(id)
+        .keys(deps.storage, start, None, Order::Ascending)
+    {
+        if msgs.len() >= limit {
+            break;
+        }
+        let reply_id = res?;
+        if is_message_hidden(deps.storage, threshold, reply_id)? {
+            continue;
         }
+        if let Some(msg) = MESSAGES.may_load(deps.storage, reply_id)? {
+            msgs.push(message_view(reply_id, msg));
+        }
+    }
 
-        Ok(msgs)
-    } else {
-        Ok(vec![])
+    Ok(msgs)
+}
+
+// Turns a start_after cursor into range bounds for either direction
+fn message_bounds(
+    start_after: Option<MessageId>,
+    order: ListOrder,
+) -> (
+    Option<Bound<'static, MessageId>>,
+    Option<Bound<'static, MessageId>>,
+    Order,
+) {
+    let start = start_after.map(Bound::exclusive);
+    match order {
+        ListOrder::Ascending => (start, None, Order::Ascending),
+        ListOrder::Descending => (None, start, Order::Descending),
     }
 }
 
-fn query_user(deps: Deps, addr: Addr) -> StdResult<Option<UserStats>> {
-    let s = USER_STATS.may_load(deps.storage, addr)?;
-    Ok(s)
+// Older messages were stored without their id, meta is decoded when it fits MessageMeta
+fn message_view(id: MessageId, msg: Message) -> MessageResponse {
+    MessageResponse {
+        meta_decoded: msg.meta.as_ref().and_then(|m| from_json(m).ok()),
+        message: Message {
+            id: Some(id),
+            ..msg
+        },
+        author: None,
+    }
+}
+
+fn report_threshold(deps: Deps) -> StdResult<u64> {
+    let c = CONFIG.load(deps.storage)?;
+    Ok(c.report_threshold.unwrap_or(DEFAULT_REPORT_THRESHOLD))
+}
+
+fn query_reported_messages(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(MessageResponse, u64)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let mut msgs: Vec<(MessageResponse, u64)> = vec![];
+
+    for res in MESSAGE_REPORT_COUNTS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+    {
+        let (id, reports) = res?;
+        if let Some(msg) = MESSAGES.may_load(deps.storage, id)? {
+            msgs.push((message_view(id, msg), reports));
+        }
+    }
+
+    Ok(msgs)
+}
+
+fn query_nft_ratings(
+    deps: Deps,
+    class_id: String,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+    include_profiles: Option<bool>,
+) -> StdResult<Vec<RatingInfo>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    NFT_RATINGS
+        .prefix(class_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| {
+            let (addr, rate) = res?;
+            let profile = if include_profiles.unwrap_or(false) {
+                PROFILES.may_load(deps.storage, addr.clone())?
+            } else {
+                None
+            };
+            Ok(RatingInfo {
+                addr,
+                rate,
+                profile,
+            })
+        })
+        .collect()
+}
+
+fn query_profile(deps: Deps, addr: Addr) -> StdResult<Option<Profile>> {
+    PROFILES.may_load(deps.storage, addr)
+}
+
+fn query_resolve_name(deps: Deps, name: String) -> StdResult<Option<Addr>> {
+    PROFILE_NAMES.may_load(deps.storage, name.to_ascii_lowercase())
+}
+
+// Accounts that were only followed have no stats yet, but still show their counts
+fn query_user(deps: Deps, env: Env, addr: Addr) -> StdResult<Option<UserStatsResponse>> {
+    let s = USER_STATS.may_load(deps.storage, addr.clone())?;
+    let follows = FOLLOW_COUNTS.may_load(deps.storage, addr)?;
+    if s.is_none() && follows.is_none() {
+        return Ok(None);
+    }
+    let s = s.unwrap_or_default();
+    let c = CONFIG.load(deps.storage)?;
+    let now = env.block.time.seconds();
+    Ok(Some(UserStatsResponse {
+        progress: unlock_progress(&c, &s),
+        follows: follows.unwrap_or_default(),
+        stats: UserStats {
+            current_streak: Some(live_streak(&s, now)),
+            ..s
+        },
+    }))
+}
+
+fn query_user_activity(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, u64)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_after.map(Bound::exclusive);
+
+    USER_ACTIVE_DAYS
+        .prefix(addr)
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .collect()
+}
+
+// Broken current streaks are still indexed at their old score, so those get skipped, as do banned users
+fn query_user_leaderboard(
+    deps: Deps,
+    env: Env,
+    metric: UserMetric,
+    period: Option<LeaderboardPeriod>,
+    start_after: Option<(u64, Addr)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(Addr, u64)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let now = env.block.time.seconds();
+    let period = period.unwrap_or(LeaderboardPeriod::AllTime);
+    let streak = matches!(
+        metric,
+        UserMetric::CurrentStreak | UserMetric::LongestStreak | UserMetric::ActiveDays
+    );
+    if streak && period != LeaderboardPeriod::AllTime {
+        return Err(StdError::generic_err(format!(
+            "{} is only ranked all time",
+            metric.as_str()
+        )));
+    }
+    if metric != UserMetric::CurrentStreak {
+        let key = leaderboard_key(&metric, &period, now);
+        return ranked_users(deps, key, start_after, limit);
+    }
+
+    // merge the boards of users active today & yesterday, older streaks are broken
+    let today = now.saturating_sub(now % CALENDAR_DAY_IN_SECONDS);
+    let mut ranks: Vec<(Addr, u64)> = vec![];
+    for day in [today, today.saturating_sub(CALENDAR_DAY_IN_SECONDS)] {
+        ranks.extend(ranked_users(
+            deps,
+            streak_board_key(day),
+            start_after.clone(),
+            limit,
+        )?);
+    }
+    ranks.sort_by(|a, b| (b.1, &b.0).cmp(&(a.1, &a.0)));
+    ranks.truncate(limit);
+
+    Ok(ranks)
+}
+
+// Highest scores first on one board, permanently banned users are left out
+fn ranked_users(
+    deps: Deps,
+    key: String,
+    start_after: Option<(u64, Addr)>,
+    limit: usize,
+) -> StdResult<Vec<(Addr, u64)>> {
+    let end = start_after.map(Bound::exclusive);
+    let mut ranks: Vec<(Addr, u64)> = vec![];
+
+    for res in USER_RANKS
+        .sub_prefix(key)
+        .keys(deps.storage, None, end, Order::Descending)
+    {
+        if ranks.len() >= limit {
+            break;
+        }
+        let (score, addr) = res?;
+        let banned = USER_BANS
+            .may_load(deps.storage, addr.clone())?
+            .map(|ban| ban.until.is_none())
+            .unwrap_or(false);
+        if banned {
+            continue;
+        }
+        ranks.push((addr, score));
+    }
+
+    Ok(ranks)
+}
+
+fn query_user_badges(deps: Deps, addr: Addr) -> StdResult<Vec<Badge>> {
+    USER_BADGES
+        .prefix(addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|res| res.map(|(_, badge)| badge))
+        .collect()
 }
 
 fn query_config(deps: Deps) -> StdResult<Config> {
@@ -910,6 +4535,244 @@ fn query_stats(deps: Deps) -> StdResult<TotalStats> {
     Ok(s)
 }
 
+fn query_paused(deps: Deps) -> StdResult<Vec<Action>> {
+    let p = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    Ok(p)
+}
+
+fn query_followers(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    FOLLOWERS
+        .prefix(addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+fn query_following(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    FOLLOWING
+        .prefix(addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+// Takes the newest page from each followed account, then merges by activity seq
+fn query_feed(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Activity>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let following = FOLLOWING
+        .prefix(addr)
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(MAX_FEED_SOURCES)
+        .collect::<StdResult<Vec<Addr>>>()?;
+
+    let mut feed: Vec<Activity> = vec![];
+    for followed in following.into_iter() {
+        // keeps going past removed items, so nothing newer than the cursor gets skipped
+        let end = start_after.map(Bound::exclusive);
+        let mut taken = 0;
+        for res in USER_ACTIVITY
+            .prefix(followed)
+            .range(deps.storage, None, end, Order::Descending)
+        {
+            if taken >= limit {
+                break;
+            }
+            let (id, activity) = res?;
+            if !is_activity_live(deps.storage, &activity)? {
+                continue;
+            }
+            feed.push(Activity {
+                id: Some(id),
+                ..activity
+            });
+            taken += 1;
+        }
+    }
+    feed.sort_by_key(|a| std::cmp::Reverse(a.id));
+    feed.truncate(limit);
+
+    Ok(feed)
+}
+
+fn query_conversation(
+    deps: Deps,
+    a: Addr,
+    b: Addr,
+    class_id: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+    order: Option<ListOrder>,
+) -> StdResult<Vec<DirectMessage>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let (min, max, order) = message_bounds(start_after, order.unwrap_or(ListOrder::Ascending));
+
+    DIRECT_MESSAGES
+        .prefix(conversation_key(&a, &b, &class_id))
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .map(|res| res.map(|(_, dm)| dm))
+        .collect()
+}
+
+fn query_conversations(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<(Addr, Option<String>)>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Conversation>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start =
+        start_after.map(|(with, class_id)| Bound::exclusive((with, class_id.unwrap_or_default())));
+
+    USER_CONVERSATIONS
+        .sub_prefix(addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, convo)| convo))
+        .collect()
+}
+
+fn query_blocked_users(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    DM_BLOCKS
+        .prefix(addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+fn query_notifications(
+    deps: Deps,
+    addr: Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Notification>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let end = start_after.map(Bound::exclusive);
+    let last_read = NOTIFICATIONS_READ
+        .may_load(deps.storage, addr.clone())?
+        .unwrap_or_default();
+
+    NOTIFICATIONS
+        .prefix(addr)
+        .range(deps.storage, None, end, Order::Descending)
+        .take(limit)
+        .map(|res| {
+            let (id, n) = res?;
+            Ok(Notification {
+                id: Some(id),
+                read: Some(id <= last_read),
+                ..n
+            })
+        })
+        .collect()
+}
+
+fn query_banned_words(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    BANNED_WORDS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+fn query_collections(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, CollectionStatus)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    COLLECTIONS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+fn query_chains(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, CollectionStatus)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    CHAINS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+fn query_collection_eligible(
+    deps: Deps,
+    contract_addr: String,
+    chain_id: Option<String>,
+) -> StdResult<bool> {
+    let c = CONFIG.load(deps.storage)?;
+    is_collection_eligible(
+        deps.storage,
+        c.allowlist_only.unwrap_or(false),
+        &contract_addr,
+        chain_id.as_deref(),
+    )
+}
+
+// Mutes that have run out are skipped after the page is read, so a page can come back short
+fn query_banned_users(
+    deps: Deps,
+    env: Env,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(Addr, UserBan)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let now = env.block.time.seconds();
+
+    USER_BANS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .filter(|res| match res {
+            Ok((_, ban)) => ban.until.map(|until| until > now).unwrap_or(true),
+            Err(_) => true,
+        })
+        .collect()
+}
+
 fn query_class_id(contract_addr: String, token_id: String) -> StdResult<String> {
     Ok(format!("{}{}", contract_addr, token_id))
 }
@@ -1,3 +1,4 @@
+use crate::types::Action;
 use cosmwasm_std::StdError;
 use thiserror::Error;
 
@@ -12,6 +13,15 @@ pub enum ContractError {
     #[error("Error with field limits")]
     FieldLimitExceeded {},
 
+    #[error("Action paused: {action:?}")]
+    Paused { action: Action },
+
+    #[error("Address is banned")]
+    Banned {},
+
+    #[error("Address is muted until {until}")]
+    Muted { until: u64 },
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
 }
@@ -4,4 +4,7 @@ pub mod msg;
 pub mod state;
 pub mod types;
 
+#[cfg(test)]
+mod tests;
+
 pub use crate::error::ContractError;
@@ -1,4 +1,7 @@
-use crate::types::{ConfigHr, ListKind, ListSort, TokenUri};
+use crate::types::{
+    Action, Avatar, CollectionStatus, ConfigHr, LeaderboardPeriod, ListKind, ListOrder, ListSort,
+    MessageId, TokenUri, UserMetric, Visibility,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cosmwasm_std::{Addr, Binary, Coin};
 
@@ -8,16 +11,71 @@ pub struct InstantiateMsg {
     pub save_fee: Coin,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {}
+
 #[cw_serde]
 pub enum ExecuteMsg {
     ChangeConfig {
-        config: ConfigHr,
+        config: Box<ConfigHr>,
     },
 
     Message {
         class_id: String,
         message: String,
         meta: Option<Binary>,
+        reply_to: Option<MessageId>,
+    },
+
+    // Authors only, within the configured edit window
+    EditMessage {
+        id: MessageId,
+        message: String,
+        meta: Option<Binary>,
+    },
+    DeleteOwnMessage {
+        id: MessageId,
+    },
+
+    // Replaces the whole profile, dropping the name frees it up
+    SetProfile {
+        display_name: Option<String>,
+        bio: Option<String>,
+        avatar: Option<Avatar>,
+    },
+
+    Follow {
+        addr: Addr,
+    },
+    Unfollow {
+        addr: Addr,
+    },
+
+    // Optionally about a specific NFT, each NFT gets its own thread
+    SendDirectMessage {
+        to: Addr,
+        class_id: Option<String>,
+        content: Binary,
+    },
+    BlockUser {
+        addr: Addr,
+    },
+    UnblockUser {
+        addr: Addr,
+    },
+
+    // Marks all notifications up to & including this id as read
+    MarkNotificationsRead {
+        up_to: u64,
+    },
+
+    React {
+        id: MessageId,
+        reaction: String,
+    },
+    Unreact {
+        id: MessageId,
+        reaction: String,
     },
 
     Rate {
@@ -31,68 +89,393 @@ pub enum ExecuteMsg {
         chain_id: Option<String>,
     },
 
+    // Collection defaults to "Favorites"
     Save {
         class_id: String,
+        collection: Option<String>,
+        note: Option<String>,
     },
 
     Unsave {
         class_id: String,
+        collection: Option<String>,
+    },
+
+    CreateCollection {
+        name: String,
+    },
+    RenameCollection {
+        name: String,
+        new_name: String,
+    },
+    DeleteCollection {
+        name: String,
+    },
+    MoveSaved {
+        class_id: String,
+        from: String,
+        to: String,
+    },
+    // Display hint for saved lists, doesn't make them private (see Visibility)
+    SetSavedVisibility {
+        visibility: Visibility,
+    },
+    PublishCollection {
+        name: String,
+        title: String,
+        description: Option<String>,
+    },
+    UnpublishCollection {
+        name: String,
+    },
+    SaveGallery {
+        owner: Addr,
+        name: String,
+    },
+    UnsaveGallery {
+        owner: Addr,
+        name: String,
+    },
+
+    // Bid for the next featured window, outbid funds are refunded
+    Boost {
+        class_id: String,
+    },
+
+    ReportMessage {
+        id: u64,
+        reason: String,
     },
 
     // Only Admin:
     RemoveMessage {
         id: u64,
     },
+    ReviewMessage {
+        id: u64,
+        remove: bool,
+    },
+    SetCollectionStatus {
+        contract_addr: String,
+        status: Option<CollectionStatus>,
+    },
+    SetChainStatus {
+        chain_id: String,
+        status: Option<CollectionStatus>,
+    },
+    HideNft {
+        class_id: String,
+    },
+    UnhideNft {
+        class_id: String,
+    },
     Withdraw {
         receiver: Addr,
     },
+    // Purging removes the user's messages & ratings in batches, see PurgeUser
+    BanUser {
+        addr: Addr,
+        purge: Option<bool>,
+    },
+    // Continues a purge started by BanUser, call until "done" is true
+    PurgeUser {
+        addr: Addr,
+        limit: Option<u32>,
+    },
+    MuteUser {
+        addr: Addr,
+        until: u64,
+    },
+    UnbanUser {
+        addr: Addr,
+    },
+    SetPaused {
+        action: Action,
+        paused: bool,
+    },
+    SetBannedWords {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    // Fills the all time user boards after a migrate, call until "done" is true
+    SeedLeaderboards {
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
-    #[returns(Vec<crate::types::Nft>)]
+    #[returns(Vec<(crate::types::Nft, crate::types::RateCount)>)]
     GetList { kind: ListKind, sort: ListSort },
 
-    #[returns(Vec<crate::types::Nft>)]
+    #[returns(Option<crate::types::Nft>)]
     GetCurrentNft {},
 
-    #[returns(Vec<crate::types::Nft>)]
+    /// NFT featured for the current boost window
+    #[returns(Option<crate::types::BoostBid>)]
+    GetBoost {},
+
+    /// Leading bid for the next boost window
+    #[returns(Option<crate::types::BoostBid>)]
+    GetBoostAuction {},
+
+    /// Current slot first, then upcoming NFTs with their start times
+    #[returns(Vec<crate::types::ScheduledNft>)]
+    GetSchedule { limit: Option<u32> },
+
+    /// 0-based position in the queue, same as `Nft.index`
+    #[returns(Option<crate::types::Nft>)]
     GetNftByIndex { index: u32 },
 
-    #[returns(Vec<crate::types::Nft>)]
+    #[returns(Option<crate::types::Nft>)]
     GetNftByClassId { class_id: String },
 
+    #[returns(Vec<crate::types::NftInfo>)]
+    GetNfts {
+        start_index: Option<u32>,
+        limit: Option<u32>,
+        order: Option<ListOrder>,
+        include_rates: Option<bool>,
+    },
+
+    /// Saved NFTs across all of a user's collections
+    /// NOTE: `viewer` is self-declared, visibility is a UI hint and saved lists are public state
     #[returns(Vec<crate::types::Nft>)]
-    GetUserNftSaved { addr: Addr },
+    GetUserNftSaved {
+        addr: Addr,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        viewer: Option<Addr>,
+    },
+
+    #[returns(Vec<(Addr, u64)>)]
+    GetNftSavers {
+        class_id: String,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        viewer: Option<Addr>,
+    },
+
+    #[returns(u64)]
+    GetNftSaveCount { class_id: String },
+
+    #[returns(Vec<crate::types::SavedCollection>)]
+    GetUserCollections {
+        addr: Addr,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        viewer: Option<Addr>,
+    },
+
+    #[returns(Vec<crate::types::SavedNft>)]
+    GetCollectionNfts {
+        addr: Addr,
+        collection: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        viewer: Option<Addr>,
+    },
+
+    #[returns(crate::types::Visibility)]
+    GetSavedVisibility { addr: Addr },
+
+    #[returns(Option<crate::types::Gallery>)]
+    GetGallery { owner: Addr, name: String },
+
+    #[returns(Vec<crate::types::Gallery>)]
+    GetGalleries {
+        start_after: Option<(Addr, String)>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Vec<crate::types::Gallery>)]
+    GetUserSavedGalleries {
+        addr: Addr,
+        start_after: Option<(Addr, String)>,
+        limit: Option<u32>,
+    },
 
     #[returns(Option<crate::types::Rate>)]
     GetUserNftRate { addr: Addr, class_id: String },
 
     #[returns(bool)]
-    UserHasSavedNft { addr: Addr, class_id: String },
+    UserHasSavedNft {
+        addr: Addr,
+        class_id: String,
+        viewer: Option<Addr>,
+    },
 
-    #[returns(Vec<crate::types::RateCounts>)]
+    #[returns(Option<crate::types::RateCounts>)]
     GetNftRate { class_id: String },
 
-    #[returns(Vec<crate::types::Message>)]
+    /// Newest first by default
+    #[returns(Vec<crate::types::MessageResponse>)]
     GetAllMessages {
-        from_index: Option<u64>,
-        limit: Option<u64>,
+        start_after: Option<MessageId>,
+        limit: Option<u32>,
+        order: Option<ListOrder>,
+        include_profiles: Option<bool>,
+    },
+
+    /// Oldest first by default
+    #[returns(Vec<crate::types::MessageResponse>)]
+    GetNftMessages {
+        class_id: String,
+        start_after: Option<MessageId>,
+        limit: Option<u32>,
+        order: Option<ListOrder>,
+        include_profiles: Option<bool>,
+    },
+
+    /// Replies to a message, oldest first
+    #[returns(Vec<crate::types::MessageResponse>)]
+    GetThread {
+        id: MessageId,
+        start_after: Option<MessageId>,
+        limit: Option<u32>,
+        include_profiles: Option<bool>,
     },
 
-    #[returns(Vec<crate::types::Message>)]
-    GetNftMessages { class_id: String },
+    #[returns(Vec<(crate::types::MessageResponse, u64)>)]
+    GetReportedMessages {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 
-    #[returns(Vec<crate::types::UserStats>)]
+    #[returns(Vec<crate::types::RatingInfo>)]
+    GetNftRatings {
+        class_id: String,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+        include_profiles: Option<bool>,
+    },
+
+    #[returns(Option<crate::types::UserStatsResponse>)]
     GetUser { addr: Addr },
 
+    #[returns(Vec<crate::types::Badge>)]
+    GetUserBadges { addr: Addr },
+
+    /// Ratings per day bucket, newest first
+    #[returns(Vec<(u64, u64)>)]
+    GetUserActivity {
+        addr: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Highest first, pass the last (score, addr) as start_after
+    #[returns(Vec<(Addr, u64)>)]
+    GetUserLeaderboard {
+        metric: UserMetric,
+        period: Option<LeaderboardPeriod>,
+        start_after: Option<(u64, Addr)>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Option<crate::types::Profile>)]
+    GetProfile { addr: Addr },
+
+    /// Display name => owner, case-insensitive
+    #[returns(Option<Addr>)]
+    ResolveName { name: String },
+
     #[returns(crate::types::Config)]
     GetConfig {},
 
     #[returns(crate::types::TotalStats)]
     GetTotalStats {},
 
+    #[returns(Vec<crate::types::Action>)]
+    GetPaused {},
+
+    #[returns(Vec<Addr>)]
+    GetFollowers {
+        addr: Addr,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Vec<Addr>)]
+    GetFollowing {
+        addr: Addr,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
+    /// Newest first. Only the first 100 followed accounts (by address) are merged in
+    #[returns(Vec<crate::types::Activity>)]
+    GetFeed {
+        addr: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Oldest first by default
+    #[returns(Vec<crate::types::DirectMessage>)]
+    GetConversation {
+        a: Addr,
+        b: Addr,
+        class_id: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+        order: Option<ListOrder>,
+    },
+
+    #[returns(Vec<crate::types::Conversation>)]
+    GetConversations {
+        addr: Addr,
+        /// (with, class_id) of the last conversation on the previous page
+        start_after: Option<(Addr, Option<String>)>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Vec<Addr>)]
+    GetBlockedUsers {
+        addr: Addr,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
+    /// Newest first
+    #[returns(Vec<crate::types::Notification>)]
+    GetNotifications {
+        addr: Addr,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Vec<String>)]
+    GetBannedWords {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Vec<(String, crate::types::CollectionStatus)>)]
+    GetCollections {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Vec<(String, crate::types::CollectionStatus)>)]
+    GetChains {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(bool)]
+    IsCollectionEligible {
+        contract_addr: String,
+        chain_id: Option<String>,
+    },
+
+    #[returns(Vec<(Addr, crate::types::UserBan)>)]
+    GetBannedUsers {
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    },
+
     #[returns(String)]
     GetClassId {
         contract_addr: String,
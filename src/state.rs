@@ -1,24 +1,116 @@
 use std::collections::HashMap;
 
-use crate::types::{Config, Message, Nft, Rate, RateCount, RateCounts, TotalStats, UserStats};
-use cosmwasm_std::Addr;
+use crate::types::{
+    Action, Activity, Badge, BoostBid, CollectionStatus, Config, Conversation, DirectMessage,
+    FollowCounts, Gallery, LeaderboardSeed, Message, Nft, Notification, PendingNft, Profile,
+    PurgeCursor, Rate, RateCount, RateCounts, Report, SavedCollection, SavedMeta, ScheduledNft,
+    TotalStats, UserBan, UserStats, Visibility,
+};
+use cosmwasm_std::{Addr, Empty};
 use cw_storage_plus::{Deque, Item, Map};
 
 pub const CONFIG: Item<Config> = Item::new("c");
 pub const STATS: Item<TotalStats> = Item::new("s");
 
+// Circuit breaker, any action in here is blocked until unpaused
+pub const PAUSED: Item<Vec<Action>> = Item::new("p");
+// Lowercased words rejected in messages, managed by the owner
+pub const BANNED_WORDS: Map<String, Empty> = Map::new("bw");
+
 // User stuffz
 pub const USER_STATS: Map<Addr, UserStats> = Map::new("u");
+// DEPRECATED: flat saved list, only read during migrate into SAVED_NFTS
 pub const USER_SAVED: Map<Addr, Vec<String>> = Map::new("us");
+// Saved NFTs grouped into named collections per user
+pub const SAVED_COLLECTIONS: Map<(Addr, String), SavedCollection> = Map::new("sco");
+pub const SAVED_NFTS: Map<(Addr, String, String), SavedMeta> = Map::new("sn");
+// Saved list display preference per user, defaults to public. Not access control, all state is readable
+pub const SAVED_VISIBILITY: Map<Addr, Visibility> = Map::new("svv");
+// Published collections, keyed by (owner, collection)
+pub const GALLERIES: Map<(Addr, String), Gallery> = Map::new("g");
+// Galleries saved by other users, keyed by (saver, owner, collection)
+pub const SAVED_GALLERIES: Map<(Addr, Addr, String), u64> = Map::new("sg");
+// Who saved each NFT (value is first saved ts), only counts each user once
+pub const NFT_SAVERS: Map<(String, Addr), u64> = Map::new("nsv");
+// Same entries as NFT_SAVERS keyed by user first, so saved lists page without duplicates
+pub const USER_SAVED_NFTS: Map<(Addr, String), u64> = Map::new("usn");
+pub const NFT_SAVE_COUNTS: Map<String, u64> = Map::new("nsc");
+// Only store map of most saved items, truncated like NFT_RATE_ATH
+pub const NFT_SAVE_TOP: Map<String, u64> = Map::new("nst");
+// Banned or muted users, blocked from posting/rating/sharing
+pub const USER_BANS: Map<Addr, UserBan> = Map::new("ub");
+// Purges still running, removed once all messages & ratings have been scanned
+pub const PURGES: Map<Addr, PurgeCursor> = Map::new("pu");
+
+// Allow/deny lists for sharing, keyed by collection contract & chain id
+pub const COLLECTIONS: Map<String, CollectionStatus> = Map::new("cl");
+pub const CHAINS: Map<String, CollectionStatus> = Map::new("ch");
 
 // Linked list for nfts
 pub const LIST: Deque<String> = Deque::new("n");
 pub const NFTS: Map<String, Nft> = Map::new("nft");
 
-// All messages index based on timstamp + tx index
+// Rotation for the "current" NFT, pending is keyed by (priority rank, sequence)
+pub const SCHEDULE_CURRENT: Item<ScheduledNft> = Item::new("sc");
+pub const SCHEDULE_PENDING: Map<(u64, u64), PendingNft> = Map::new("sp");
+pub const SCHEDULE_SEQ: Item<u64> = Item::new("ss");
+
+// Boost auctions, winning bid per window keyed by window start. Bids for future windows are held in escrow
+pub const BOOST_BIDS: Map<u64, BoostBid> = Map::new("bb");
+
+// All messages index based on timstamp, bumped past the last id when a block has several
 pub const MESSAGES: Map<u64, Message> = Map::new("m");
-// A simple cache of messages for individiual NFTs ("class_id")
+pub const LAST_MESSAGE_ID: Item<u64> = Item::new("lm");
+// DEPRECATED: per-NFT id cache, only read during migrate into NFT_MESSAGES
 pub const MESSAGES_IDS: Map<String, Vec<u64>> = Map::new("mi");
+// Messages for individiual NFTs ("class_id"), keyed by (class_id, msg id)
+pub const NFT_MESSAGES: Map<(String, u64), Empty> = Map::new("nm");
+// Thread replies, keyed by (parent, reply)
+pub const MESSAGE_REPLIES: Map<(u64, u64), Empty> = Map::new("mrp");
+// One of each reaction per user per message, keyed by (msg, reaction, user)
+pub const MESSAGE_REACTIONS: Map<(u64, String, Addr), u64> = Map::new("mre");
+// First raters of each NFT & their value, scored then cleared at settlement
+pub const EARLY_RATINGS: Map<(String, Addr), u8> = Map::new("er");
+// NFTs whose reputation has been settled => ts
+pub const REPUTATION_SETTLED: Map<String, u64> = Map::new("rs");
+// Ratings per user per day bucket
+pub const USER_ACTIVE_DAYS: Map<(Addr, u64), u64> = Map::new("uad");
+// User leaderboards: (metric, score, user) sorts by score, USER_SCORES finds the old key.
+// Windowed metrics are keyed "{metric}:d:{day_ts}" & "{metric}:m:{month_ts}"
+pub const USER_RANKS: Map<(String, u64, Addr), Empty> = Map::new("ur");
+pub const USER_SCORES: Map<(String, Addr), u64> = Map::new("usc");
+// Windows with rows, keyed by ("{metric}:d" or "{metric}:m", window start) so expired ones can be pruned
+pub const USER_BOARD_WINDOWS: Map<(String, u64), Empty> = Map::new("ubw");
+// Only set between a migrate & the last SeedLeaderboards batch
+pub const LEADERBOARD_SEED: Item<LeaderboardSeed> = Item::new("lbs");
+// Badges earned per user, keyed by BadgeKind::as_str
+pub const USER_BADGES: Map<(Addr, String), Badge> = Map::new("bd");
+// Profiles, with display names registered lowercase => owner
+pub const PROFILES: Map<Addr, Profile> = Map::new("pf");
+pub const PROFILE_NAMES: Map<String, Addr> = Map::new("pn");
+// Follow graph, both directions so either side can be listed
+pub const FOLLOWING: Map<(Addr, Addr), u64> = Map::new("fw");
+pub const FOLLOWERS: Map<(Addr, Addr), u64> = Map::new("fr");
+// Kept apart from USER_STATS, so being followed doesn't create a stats row
+pub const FOLLOW_COUNTS: Map<Addr, FollowCounts> = Map::new("fc");
+// Activity log per user, keyed by (user, global activity seq)
+pub const USER_ACTIVITY: Map<(Addr, u64), Activity> = Map::new("ua");
+pub const ACTIVITY_SEQ: Item<u64> = Item::new("as");
+// Direct messages keyed by (conversation key, dm seq), see conversation_key
+pub const DIRECT_MESSAGES: Map<((Addr, Addr, String), u64), DirectMessage> = Map::new("dm");
+pub const DIRECT_MESSAGE_SEQ: Item<u64> = Item::new("dms");
+// (user, other side, class_id or "") => that user's view of the thread
+pub const USER_CONVERSATIONS: Map<(Addr, Addr, String), Conversation> = Map::new("uc");
+// (blocker, blocked) => ts, blocked users cannot DM the blocker
+pub const DM_BLOCKS: Map<(Addr, Addr), u64> = Map::new("dmb");
+// Per-user inbox for mentions & replies, keyed by (user, notification seq)
+pub const NOTIFICATIONS: Map<(Addr, u64), Notification> = Map::new("nt");
+pub const NOTIFICATION_SEQ: Item<u64> = Item::new("ns");
+// Highest notification id each user has marked read
+pub const NOTIFICATIONS_READ: Map<Addr, u64> = Map::new("ntr");
+// Community reports, one per user per message, plus a running count per message
+pub const MESSAGE_REPORTS: Map<(u64, Addr), Report> = Map::new("mr");
+pub const MESSAGE_REPORT_COUNTS: Map<u64, u64> = Map::new("mrc");
 
 // Ratings:
 // Timeframes: Day, Month, Year - compute SMA for 24-365 points per NFT
@@ -0,0 +1,2099 @@
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{coin, from_json, Addr, Env, OwnedDeps, Response};
+
+use crate::contract::{execute, instantiate, migrate, query};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::state::{LAST_MESSAGE_ID, LIST, NFTS};
+use crate::types::{Nft, TokenUri, DEFAULT_SLOT_DURATION};
+
+type Deps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+const OWNER: &str = "owner";
+
+fn setup() -> (Deps, Env) {
+    let mut deps = mock_dependencies();
+    let env = mock_env();
+    instantiate(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        InstantiateMsg {
+            share_fee: coin(1, "ustars"),
+            save_fee: coin(1, "ustars"),
+        },
+    )
+    .unwrap();
+    (deps, env)
+}
+
+fn share(deps: &mut Deps, env: &Env, class_id: &str) {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::Share {
+            class_id: class_id.to_string(),
+            token: TokenUri {
+                contract_addr: Addr::unchecked("collection"),
+                id: "1".to_string(),
+                data_uri: None,
+            },
+            chain_id: None,
+        },
+    )
+    .unwrap();
+}
+
+fn nft_by_index(deps: &Deps, env: &Env, index: u32) -> Option<Nft> {
+    from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetNftByIndex { index },
+        )
+        .unwrap(),
+    )
+    .unwrap()
+}
+
+// Last release before the one time migrations
+fn set_old_version(deps: &mut Deps) {
+    cw2::set_contract_version(deps.as_mut().storage, "nft-hop", "0.1.4").unwrap();
+}
+
+fn rate(deps: &mut Deps, env: &Env, sender: &str, class_id: &str, v: u8) {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(sender, &[]),
+        ExecuteMsg::Rate {
+            class_id: class_id.to_string(),
+            v,
+        },
+    )
+    .unwrap();
+}
+
+// Partial ConfigHr as JSON, so tests only spell out what they change
+fn set_config(deps: &mut Deps, env: &Env, config: &str) {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::ChangeConfig {
+            config: Box::new(from_json(config).unwrap()),
+        },
+    )
+    .unwrap();
+}
+
+// Posts a message & returns its id
+fn post(deps: &mut Deps, env: &Env, sender: &str, class_id: &str, message: &str) -> u64 {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(sender, &[]),
+        ExecuteMsg::Message {
+            class_id: class_id.to_string(),
+            message: message.to_string(),
+            meta: None,
+            reply_to: None,
+        },
+    )
+    .unwrap();
+    LAST_MESSAGE_ID.load(deps.as_ref().storage).unwrap()
+}
+
+fn attr(res: &Response, key: &str) -> String {
+    res.attributes
+        .iter()
+        .find(|a| a.key == key)
+        .map(|a| a.value.clone())
+        .unwrap_or_default()
+}
+
+#[test]
+fn nft_by_index_bounds() {
+    let (mut deps, env) = setup();
+    for class_id in ["c0", "c1", "c2"] {
+        share(&mut deps, &env, class_id);
+    }
+    let len = LIST.len(deps.as_ref().storage).unwrap();
+    assert_eq!(len, 3);
+
+    let first = nft_by_index(&deps, &env, 0).unwrap();
+    assert_eq!(first.class_id, "c0");
+    assert_eq!(first.index, Some(0));
+
+    let last = nft_by_index(&deps, &env, len - 1).unwrap();
+    assert_eq!(last.class_id, "c2");
+    assert_eq!(last.index, Some(u64::from(len - 1)));
+
+    assert_eq!(nft_by_index(&deps, &env, len), None);
+}
+
+#[test]
+fn migrate_rewrites_one_based_index() {
+    let (mut deps, env) = setup();
+    for class_id in ["c0", "c1", "c2"] {
+        share(&mut deps, &env, class_id);
+    }
+
+    // store the indexes the way older versions did
+    for (i, class_id) in ["c0", "c1", "c2"].iter().enumerate() {
+        let mut nft = NFTS
+            .load(deps.as_ref().storage, class_id.to_string())
+            .unwrap();
+        nft.index = Some(i as u64 + 1);
+        NFTS.save(deps.as_mut().storage, class_id.to_string(), &nft)
+            .unwrap();
+    }
+
+    set_old_version(&mut deps);
+    let res = migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|a| a.key == "nfts_reindexed" && a.value == "3"));
+
+    for (i, class_id) in ["c0", "c1", "c2"].iter().enumerate() {
+        let nft = NFTS
+            .load(deps.as_ref().storage, class_id.to_string())
+            .unwrap();
+        assert_eq!(nft.index, Some(i as u64));
+        assert_eq!(
+            nft_by_index(&deps, &env, i as u32).unwrap().class_id,
+            *class_id
+        );
+    }
+}
+
+fn current_nft(deps: &Deps, env: &Env) -> Option<Nft> {
+    from_json(query(deps.as_ref(), env.clone(), QueryMsg::GetCurrentNft {}).unwrap()).unwrap()
+}
+
+#[test]
+fn schedule_skips_hidden_and_expired_slots() {
+    let (mut deps, mut env) = setup();
+    share(&mut deps, &env, "c0");
+    share(&mut deps, &env, "c1");
+    assert_eq!(current_nft(&deps, &env).unwrap().class_id, "c0");
+
+    // hiding the live NFT hands its slot to the next one right away
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::HideNft {
+            class_id: "c0".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(current_nft(&deps, &env).unwrap().class_id, "c1");
+
+    // nothing queued after c1, so it stays up past the end of its slot
+    env.block.time = env.block.time.plus_seconds(DEFAULT_SLOT_DURATION);
+    assert_eq!(current_nft(&deps, &env).unwrap().class_id, "c1");
+
+    // until something new is shared
+    share(&mut deps, &env, "c2");
+    assert_eq!(current_nft(&deps, &env).unwrap().class_id, "c2");
+
+    // a hidden NFT falls back to the latest visible one
+    share(&mut deps, &env, "c3");
+    env.block.time = env.block.time.plus_seconds(DEFAULT_SLOT_DURATION * 2);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::HideNft {
+            class_id: "c3".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(current_nft(&deps, &env).unwrap().class_id, "c2");
+}
+
+mod boost {
+    use cosmwasm_std::{coin, coins, from_json, Addr, Empty};
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+    use crate::types::{BoostBid, ConfigHr, TokenUri, DEFAULT_BOOST_DURATION};
+
+    const OWNER: &str = "owner";
+    const ALICE: &str = "alice";
+    const BOB: &str = "bob";
+    const DENOM: &str = "ustars";
+
+    fn contract() -> Box<dyn Contract<Empty>> {
+        Box::new(ContractWrapper::new(execute, instantiate, query))
+    }
+
+    fn setup() -> (App, Addr) {
+        let mut app = App::new(|router, _, storage| {
+            for user in [ALICE, BOB] {
+                router
+                    .bank
+                    .init_balance(storage, &Addr::unchecked(user), coins(1000, DENOM))
+                    .unwrap();
+            }
+        });
+        let code_id = app.store_code(contract());
+        let addr = app
+            .instantiate_contract(
+                code_id,
+                Addr::unchecked(OWNER),
+                &InstantiateMsg {
+                    share_fee: coin(1, DENOM),
+                    save_fee: coin(1, DENOM),
+                },
+                &[],
+                "nfthop",
+                None,
+            )
+            .unwrap();
+        let config: ConfigHr = from_json(format!(
+            r#"{{"boost_min_bid":{{"denom":"{}","amount":"10"}}}}"#,
+            DENOM
+        ))
+        .unwrap();
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            addr.clone(),
+            &ExecuteMsg::ChangeConfig {
+                config: Box::new(config),
+            },
+            &[],
+        )
+        .unwrap();
+        for class_id in ["c0", "c1"] {
+            app.execute_contract(
+                Addr::unchecked(OWNER),
+                addr.clone(),
+                &ExecuteMsg::Share {
+                    class_id: class_id.to_string(),
+                    token: TokenUri {
+                        contract_addr: Addr::unchecked("collection"),
+                        id: "1".to_string(),
+                        data_uri: None,
+                    },
+                    chain_id: None,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+        (app, addr)
+    }
+
+    fn bid(app: &mut App, addr: &Addr, sender: &str, class_id: &str, amount: u128) -> bool {
+        app.execute_contract(
+            Addr::unchecked(sender),
+            addr.clone(),
+            &ExecuteMsg::Boost {
+                class_id: class_id.to_string(),
+            },
+            &coins(amount, DENOM),
+        )
+        .is_ok()
+    }
+
+    fn balance(app: &App, addr: &str) -> u128 {
+        app.wrap().query_balance(addr, DENOM).unwrap().amount.u128()
+    }
+
+    fn boost(app: &App, addr: &Addr, msg: &QueryMsg) -> Option<BoostBid> {
+        app.wrap().query_wasm_smart(addr, msg).unwrap()
+    }
+
+    #[test]
+    fn bid_and_outbid_refund() {
+        let (mut app, addr) = setup();
+        assert!(!bid(&mut app, &addr, ALICE, "c0", 5));
+        assert!(bid(&mut app, &addr, ALICE, "c0", 50));
+        assert_eq!(balance(&app, ALICE), 950);
+
+        let leading = boost(&app, &addr, &QueryMsg::GetBoostAuction {}).unwrap();
+        assert_eq!(leading.bidder, ALICE);
+        assert_eq!(leading.end - leading.start, DEFAULT_BOOST_DURATION);
+
+        // has to beat the leading bid, which then goes back to its bidder
+        assert!(!bid(&mut app, &addr, BOB, "c1", 50));
+        assert!(bid(&mut app, &addr, BOB, "c1", 60));
+        assert_eq!(balance(&app, ALICE), 1000);
+        assert_eq!(balance(&app, BOB), 940);
+        let leading = boost(&app, &addr, &QueryMsg::GetBoostAuction {}).unwrap();
+        assert_eq!(leading.class_id, "c1");
+    }
+
+    #[test]
+    fn window_rollover() {
+        let (mut app, addr) = setup();
+        assert!(bid(&mut app, &addr, ALICE, "c0", 50));
+        assert_eq!(boost(&app, &addr, &QueryMsg::GetBoost {}), None);
+
+        let start = boost(&app, &addr, &QueryMsg::GetBoostAuction {})
+            .unwrap()
+            .start;
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(start - block.time.seconds())
+        });
+        let live = boost(&app, &addr, &QueryMsg::GetBoost {}).unwrap();
+        assert_eq!(live.class_id, "c0");
+        assert_eq!(boost(&app, &addr, &QueryMsg::GetBoostAuction {}), None);
+
+        // the live window's bid is spent, a new bid is for the one after
+        assert!(bid(&mut app, &addr, BOB, "c1", 10));
+        assert_eq!(balance(&app, ALICE), 950);
+
+        app.update_block(|block| block.time = block.time.plus_seconds(DEFAULT_BOOST_DURATION));
+        let live = boost(&app, &addr, &QueryMsg::GetBoost {}).unwrap();
+        assert_eq!(live.class_id, "c1");
+    }
+
+    #[test]
+    fn withdraw_holds_back_escrow() {
+        let (mut app, addr) = setup();
+        assert!(bid(&mut app, &addr, ALICE, "c0", 50));
+        let start = boost(&app, &addr, &QueryMsg::GetBoostAuction {})
+            .unwrap()
+            .start;
+
+        let withdraw = ExecuteMsg::Withdraw {
+            receiver: Addr::unchecked("dao"),
+        };
+        app.execute_contract(Addr::unchecked(OWNER), addr.clone(), &withdraw, &[])
+            .unwrap();
+        assert_eq!(balance(&app, "dao"), 0);
+        assert_eq!(balance(&app, addr.as_str()), 50);
+
+        // changing the window length would strand the escrowed bid
+        let config: ConfigHr = from_json(r#"{"boost_duration":60}"#).unwrap();
+        assert!(app
+            .execute_contract(
+                Addr::unchecked(OWNER),
+                addr.clone(),
+                &ExecuteMsg::ChangeConfig {
+                    config: Box::new(config),
+                },
+                &[],
+            )
+            .is_err());
+
+        // once the window starts the bid is earned
+        app.update_block(|block| {
+            block.time = block.time.plus_seconds(start - block.time.seconds())
+        });
+        app.execute_contract(Addr::unchecked(OWNER), addr.clone(), &withdraw, &[])
+            .unwrap();
+        assert_eq!(balance(&app, "dao"), 50);
+    }
+
+    #[test]
+    fn hiding_refunds_escrowed_bids() {
+        let (mut app, addr) = setup();
+        assert!(bid(&mut app, &addr, ALICE, "c0", 50));
+        app.execute_contract(
+            Addr::unchecked(OWNER),
+            addr.clone(),
+            &ExecuteMsg::HideNft {
+                class_id: "c0".to_string(),
+            },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(balance(&app, ALICE), 1000);
+        assert_eq!(boost(&app, &addr, &QueryMsg::GetBoostAuction {}), None);
+    }
+}
+
+#[test]
+fn ban_purge_runs_in_batches_and_reverses_ratings() {
+    use crate::state::{
+        NFT_RATE_ATH, NFT_RATE_COUNTS, NFT_RATE_DAY_ATH, STATS, USER_BANS, USER_STATS,
+    };
+    use crate::types::DAY_IN_SECONDS;
+
+    let (mut deps, mut env) = setup();
+    set_config(&mut deps, &env, r#"{"unlock_messages":1}"#);
+    share(&mut deps, &env, "c0");
+    share(&mut deps, &env, "c1");
+    rate(&mut deps, &env, "r0", "c0", 5);
+    rate(&mut deps, &env, "spammer", "c1", 1);
+    rate(&mut deps, &env, "spammer", "c0", 1);
+    // more than one default batch
+    for i in 0..60 {
+        post(&mut deps, &env, "spammer", "c0", &format!("spam {}", i));
+        env.block.time = env.block.time.plus_seconds(1);
+    }
+
+    let ban = |deps: &mut Deps, msg: ExecuteMsg| {
+        execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg).unwrap()
+    };
+    let res = ban(
+        &mut deps,
+        ExecuteMsg::BanUser {
+            addr: Addr::unchecked("spammer"),
+            purge: Some(true),
+        },
+    );
+    assert_eq!(attr(&res, "done"), "false");
+    assert_eq!(attr(&res, "purged_messages"), "50");
+    let res = ban(
+        &mut deps,
+        ExecuteMsg::PurgeUser {
+            addr: Addr::unchecked("spammer"),
+            limit: None,
+        },
+    );
+    assert_eq!(attr(&res, "done"), "true");
+    assert_eq!(attr(&res, "purged_messages"), "10");
+    assert_eq!(attr(&res, "purged_ratings"), "2");
+    assert!(execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::PurgeUser {
+            addr: Addr::unchecked("spammer"),
+            limit: None,
+        },
+    )
+    .is_err());
+
+    let storage = deps.as_ref().storage;
+    assert!(USER_BANS.has(storage, Addr::unchecked("spammer")));
+    let rc = NFT_RATE_COUNTS.load(storage, "c0".to_string()).unwrap();
+    assert_eq!((rc.all.total, rc.all.sum), (1, 5));
+    assert_eq!((rc.day.total, rc.day.sum), (1, 5));
+    assert_eq!(
+        NFT_RATE_ATH.load(storage, "c0".to_string()).unwrap(),
+        rc.all
+    );
+    // c1 was only rated by the spammer, so it drops out of the lists
+    let rc = NFT_RATE_COUNTS.load(storage, "c1".to_string()).unwrap();
+    assert_eq!((rc.all.total, rc.day.total), (0, 0));
+    assert!(!NFT_RATE_ATH.has(storage, "c1".to_string()));
+    let now = env.block.time.seconds();
+    let day = NFT_RATE_DAY_ATH
+        .load(storage, now - now % DAY_IN_SECONDS)
+        .unwrap();
+    assert!(!day.contains_key("c1"));
+    let stats = STATS.load(storage).unwrap();
+    assert_eq!((stats.ratings, stats.messages), (1, 0));
+    let st = USER_STATS
+        .load(storage, Addr::unchecked("spammer"))
+        .unwrap();
+    assert_eq!(st.ratings, 0);
+}
+
+#[test]
+fn banned_users_page_is_bounded() {
+    let (mut deps, env) = setup();
+    for (addr, until) in [("aaa", Some(1)), ("bbb", Some(2)), ("ccc", None)] {
+        let msg = match until {
+            Some(until) => ExecuteMsg::MuteUser {
+                addr: Addr::unchecked(addr),
+                until,
+            },
+            None => ExecuteMsg::BanUser {
+                addr: Addr::unchecked(addr),
+                purge: None,
+            },
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg).unwrap();
+    }
+
+    let page = |deps: &Deps, limit: u32| -> Vec<(Addr, crate::types::UserBan)> {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetBannedUsers {
+                    start_after: None,
+                    limit: Some(limit),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    // the expired mutes fill the page without being returned
+    assert_eq!(page(&deps, 2), vec![]);
+    assert_eq!(page(&deps, 3).len(), 1);
+
+    assert!(execute(
+        deps.as_mut(),
+        env,
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UnbanUser {
+            addr: Addr::unchecked(""),
+        },
+    )
+    .is_err());
+}
+
+fn nft_messages(deps: &Deps, env: &Env, class_id: &str) -> Vec<u64> {
+    let msgs: Vec<crate::types::MessageResponse> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetNftMessages {
+                class_id: class_id.to_string(),
+                start_after: None,
+                limit: None,
+                order: None,
+                include_profiles: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    msgs.into_iter().filter_map(|m| m.message.id).collect()
+}
+
+#[test]
+fn reports_hide_messages_until_reviewed() {
+    let (mut deps, mut env) = setup();
+    set_config(
+        &mut deps,
+        &env,
+        r#"{"unlock_messages":1,"report_threshold":2}"#,
+    );
+    share(&mut deps, &env, "c0");
+    for user in ["author", "r1", "r2", "muted"] {
+        rate(&mut deps, &env, user, "c0", 4);
+    }
+    let first = post(&mut deps, &env, "author", "c0", "first");
+    env.block.time = env.block.time.plus_seconds(1);
+    let second = post(&mut deps, &env, "author", "c0", "second");
+
+    let report = |deps: &mut Deps, sender: &str, id: u64| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender, &[]),
+            ExecuteMsg::ReportMessage {
+                id,
+                reason: "spam".to_string(),
+            },
+        )
+    };
+    let review = |deps: &mut Deps, id: u64, remove: bool| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::ReviewMessage { id, remove },
+        )
+    };
+
+    // muted & banned users cannot push a message over the threshold
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::MuteUser {
+            addr: Addr::unchecked("muted"),
+            until: env.block.time.seconds() + 60,
+        },
+    )
+    .unwrap();
+    assert!(report(&mut deps, "muted", first).is_err());
+
+    report(&mut deps, "r1", first).unwrap();
+    assert!(report(&mut deps, "r1", first).is_err());
+    assert_eq!(nft_messages(&deps, &env, "c0"), vec![first, second]);
+    report(&mut deps, "r2", first).unwrap();
+    assert_eq!(nft_messages(&deps, &env, "c0"), vec![second]);
+
+    // restoring clears the count, but reporters still cannot report twice
+    review(&mut deps, first, false).unwrap();
+    assert_eq!(nft_messages(&deps, &env, "c0"), vec![first, second]);
+    assert!(report(&mut deps, "r2", first).is_err());
+
+    report(&mut deps, "r1", second).unwrap();
+    review(&mut deps, second, true).unwrap();
+    assert_eq!(nft_messages(&deps, &env, "c0"), vec![first]);
+    assert!(review(&mut deps, first, true).is_ok());
+    assert!(review(&mut deps, first, false).is_ok());
+    assert!(report(&mut deps, "r1", first).is_err());
+}
+
+#[test]
+fn collection_and_chain_lists() {
+    use crate::types::CollectionStatus;
+
+    let (mut deps, env) = setup();
+    let set = |deps: &mut Deps, msg: ExecuteMsg| {
+        execute(deps.as_mut(), env.clone(), mock_info(OWNER, &[]), msg).unwrap();
+    };
+    let eligible = |deps: &Deps, contract_addr: &str, chain_id: Option<&str>| -> bool {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::IsCollectionEligible {
+                    contract_addr: contract_addr.to_string(),
+                    chain_id: chain_id.map(str::to_string),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert!(eligible(&deps, "coll", None));
+    // only other users' shares are checked
+    share(&mut deps, &env, "c0");
+    rate(&mut deps, &env, "sharer", "c0", 4);
+    set_config(&mut deps, &env, r#"{"unlock_share":1}"#);
+
+    // deny matches any case variant, on write & on lookup
+    set(
+        &mut deps,
+        ExecuteMsg::SetCollectionStatus {
+            contract_addr: " Bad ".to_string(),
+            status: Some(CollectionStatus::Denied),
+        },
+    );
+    assert!(!eligible(&deps, "bad", None));
+    assert!(!eligible(&deps, "BAD", None));
+    assert!(execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("sharer", &[coin(1, "ustars")]),
+        ExecuteMsg::Share {
+            class_id: "c1".to_string(),
+            token: TokenUri {
+                contract_addr: Addr::unchecked("BAD"),
+                id: "1".to_string(),
+                data_uri: None,
+            },
+            chain_id: None,
+        },
+    )
+    .is_err());
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("sharer", &[coin(1, "ustars")]),
+        ExecuteMsg::Share {
+            class_id: "c1".to_string(),
+            token: TokenUri {
+                contract_addr: Addr::unchecked("good"),
+                id: "1".to_string(),
+                data_uri: None,
+            },
+            chain_id: None,
+        },
+    )
+    .unwrap();
+
+    // once a chain is listed, leaving out the chain id no longer skips the list
+    set(
+        &mut deps,
+        ExecuteMsg::SetChainStatus {
+            chain_id: "Spam-1".to_string(),
+            status: Some(CollectionStatus::Denied),
+        },
+    );
+    assert!(!eligible(&deps, "coll", Some("SPAM-1")));
+    assert!(!eligible(&deps, "coll", None));
+    assert!(eligible(&deps, "coll", Some("stargaze-1")));
+
+    // allowlist mode needs both the collection & the chain allowed
+    set_config(&mut deps, &env, r#"{"allowlist_only":true}"#);
+    assert!(!eligible(&deps, "coll", Some("stargaze-1")));
+    set(
+        &mut deps,
+        ExecuteMsg::SetCollectionStatus {
+            contract_addr: "COLL".to_string(),
+            status: Some(CollectionStatus::Allowed),
+        },
+    );
+    assert!(!eligible(&deps, "coll", Some("stargaze-1")));
+    set(
+        &mut deps,
+        ExecuteMsg::SetChainStatus {
+            chain_id: "stargaze-1".to_string(),
+            status: Some(CollectionStatus::Allowed),
+        },
+    );
+    assert!(eligible(&deps, "coll", Some("Stargaze-1")));
+    assert!(!eligible(&deps, "coll", None));
+}
+
+#[test]
+fn replies_and_reactions() {
+    use crate::state::MESSAGES;
+    use crate::types::{MessageResponse, ReactionCount};
+
+    let (mut deps, env) = setup();
+    set_config(&mut deps, &env, r#"{"unlock_messages":1}"#);
+    for class_id in ["c0", "c1"] {
+        share(&mut deps, &env, class_id);
+    }
+    rate(&mut deps, &env, "aaa", "c0", 4);
+    rate(&mut deps, &env, "bbb", "c0", 3);
+    let parent = post(&mut deps, &env, "aaa", "c0", "first");
+
+    let reply = |deps: &mut Deps, class_id: &str, reply_to: u64| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bbb", &[]),
+            ExecuteMsg::Message {
+                class_id: class_id.to_string(),
+                message: "reply".to_string(),
+                meta: None,
+                reply_to: Some(reply_to),
+            },
+        )
+    };
+    // replies stay on the parent's NFT
+    assert!(reply(&mut deps, "c1", parent).is_err());
+    assert!(reply(&mut deps, "c0", parent + 100).is_err());
+    reply(&mut deps, "c0", parent).unwrap();
+    let child = LAST_MESSAGE_ID.load(deps.as_ref().storage).unwrap();
+
+    let thread = |deps: &Deps| -> Vec<u64> {
+        let msgs: Vec<MessageResponse> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetThread {
+                    id: parent,
+                    start_after: None,
+                    limit: None,
+                    include_profiles: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        msgs.into_iter().filter_map(|m| m.message.id).collect()
+    };
+    assert_eq!(thread(&deps), vec![child]);
+
+    let react = |deps: &mut Deps, sender: &str, reaction: &str, add: bool| {
+        let reaction = reaction.to_string();
+        let msg = if add {
+            ExecuteMsg::React {
+                id: parent,
+                reaction,
+            }
+        } else {
+            ExecuteMsg::Unreact {
+                id: parent,
+                reaction,
+            }
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg)
+    };
+    let reactions = |deps: &Deps| {
+        MESSAGES
+            .load(deps.as_ref().storage, parent)
+            .unwrap()
+            .reactions
+    };
+    let fire = |count: u64| {
+        Some(vec![ReactionCount {
+            reaction: "🔥".to_string(),
+            count,
+        }])
+    };
+
+    // one of each reaction per user, only from the allowed set
+    react(&mut deps, "bbb", "🔥", true).unwrap();
+    assert!(react(&mut deps, "bbb", "🔥", true).is_err());
+    assert!(react(&mut deps, "bbb", "🍕", true).is_err());
+    react(&mut deps, "aaa", "🔥", true).unwrap();
+    assert_eq!(reactions(&deps), fire(2));
+
+    react(&mut deps, "bbb", "🔥", false).unwrap();
+    assert!(react(&mut deps, "bbb", "🔥", false).is_err());
+    assert_eq!(reactions(&deps), fire(1));
+    react(&mut deps, "aaa", "🔥", false).unwrap();
+    assert_eq!(reactions(&deps), None);
+
+    // owner swaps the allowed set
+    set_config(&mut deps, &env, r#"{"reactions":["🍕"]}"#);
+    assert!(react(&mut deps, "bbb", "🔥", true).is_err());
+    react(&mut deps, "bbb", "🍕", true).unwrap();
+
+    // removing the parent leaves the reply up, without the thread link
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RemoveMessage { id: parent },
+    )
+    .unwrap();
+    assert!(thread(&deps).is_empty());
+    assert!(MESSAGES.has(deps.as_ref().storage, child));
+}
+
+#[test]
+fn message_pages_follow_order() {
+    use crate::types::{ListOrder, MessageResponse};
+
+    let (mut deps, mut env) = setup();
+    set_config(&mut deps, &env, r#"{"unlock_messages":1}"#);
+    for class_id in ["c0", "c1"] {
+        share(&mut deps, &env, class_id);
+    }
+    rate(&mut deps, &env, "aaa", "c0", 4);
+
+    // ids stay unique within a block and grow with block time
+    let mut c0 = vec![];
+    let mut c1 = vec![];
+    for i in 0..3 {
+        c0.push(post(&mut deps, &env, "aaa", "c0", "hi"));
+        c0.push(post(&mut deps, &env, "aaa", "c0", "hi"));
+        c1.push(post(&mut deps, &env, "aaa", "c1", "hi"));
+        env.block.time = env.block.time.plus_seconds(10 + i);
+    }
+    let mut all = [c0.clone(), c1.clone()].concat();
+    all.sort_unstable();
+    all.dedup();
+    assert_eq!(all.len(), 9);
+
+    let ids = |msgs: Vec<MessageResponse>| -> Vec<u64> {
+        msgs.into_iter().filter_map(|m| m.message.id).collect()
+    };
+    let nft_page = |deps: &Deps, after: Option<u64>, order: Option<ListOrder>| {
+        ids(from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetNftMessages {
+                    class_id: "c0".to_string(),
+                    start_after: after,
+                    limit: Some(4),
+                    order,
+                    include_profiles: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap())
+    };
+    let all_page = |deps: &Deps, after: Option<u64>, order: Option<ListOrder>| {
+        ids(from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetAllMessages {
+                    start_after: after,
+                    limit: Some(5),
+                    order,
+                    include_profiles: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap())
+    };
+
+    // per NFT: oldest first by default
+    assert_eq!(nft_page(&deps, None, None), c0[..4].to_vec());
+    assert_eq!(nft_page(&deps, Some(c0[3]), None), c0[4..].to_vec());
+    let mut rev = c0.clone();
+    rev.reverse();
+    assert_eq!(
+        nft_page(&deps, None, Some(ListOrder::Descending)),
+        rev[..4].to_vec()
+    );
+    assert_eq!(
+        nft_page(&deps, Some(rev[3]), Some(ListOrder::Descending)),
+        rev[4..].to_vec()
+    );
+
+    // global: newest first by default
+    all.reverse();
+    assert_eq!(all_page(&deps, None, None), all[..5].to_vec());
+    assert_eq!(all_page(&deps, Some(all[4]), None), all[5..].to_vec());
+    all.reverse();
+    assert_eq!(
+        all_page(&deps, Some(all[4]), Some(ListOrder::Ascending)),
+        all[5..].to_vec()
+    );
+}
+
+#[test]
+fn authors_edit_within_window_and_delete() {
+    use crate::state::{MESSAGES, STATS};
+    use crate::types::DEFAULT_MESSAGE_EDIT_WINDOW;
+
+    let (mut deps, mut env) = setup();
+    set_config(&mut deps, &env, r#"{"unlock_messages":1}"#);
+    share(&mut deps, &env, "c0");
+    rate(&mut deps, &env, "aaa", "c0", 4);
+    let id = post(&mut deps, &env, "aaa", "c0", "frist");
+    let posted = env.block.time.seconds();
+
+    let edit = |deps: &mut Deps, env: &Env, sender: &str, message: &str| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender, &[]),
+            ExecuteMsg::EditMessage {
+                id,
+                message: message.to_string(),
+                meta: None,
+            },
+        )
+    };
+    assert!(edit(&mut deps, &env, "bbb", "mine now").is_err());
+    assert!(edit(&mut deps, &env, "aaa", "").is_err());
+
+    env.block.time = env.block.time.plus_seconds(DEFAULT_MESSAGE_EDIT_WINDOW);
+    edit(&mut deps, &env, "aaa", "first").unwrap();
+    let msg = MESSAGES.load(deps.as_ref().storage, id).unwrap();
+    assert_eq!(msg.message, "first");
+    assert_eq!(msg.ts, posted);
+    assert_eq!(msg.edited_at, Some(env.block.time.seconds()));
+
+    env.block.time = env.block.time.plus_seconds(1);
+    assert!(edit(&mut deps, &env, "aaa", "too late").is_err());
+
+    // a zero window turns editing off
+    set_config(&mut deps, &env, r#"{"message_edit_window":0}"#);
+    let id2 = post(&mut deps, &env, "aaa", "c0", "again");
+    assert!(execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("aaa", &[]),
+        ExecuteMsg::EditMessage {
+            id: id2,
+            message: "edited".to_string(),
+            meta: None,
+        },
+    )
+    .is_err());
+
+    // only the author deletes, and the counts follow
+    let delete = |deps: &mut Deps, sender: &str| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender, &[]),
+            ExecuteMsg::DeleteOwnMessage { id },
+        )
+    };
+    let messages = STATS.load(deps.as_ref().storage).unwrap().messages;
+    assert!(delete(&mut deps, "bbb").is_err());
+    delete(&mut deps, "aaa").unwrap();
+    assert!(delete(&mut deps, "aaa").is_err());
+    assert_eq!(
+        STATS.load(deps.as_ref().storage).unwrap().messages,
+        messages - 1
+    );
+    assert_eq!(nft_messages(&deps, &env, "c0"), vec![id2]);
+}
+
+#[test]
+fn meta_is_capped_and_typed() {
+    use crate::types::{MessageMeta, MessageResponse, DEFAULT_MAX_META_SIZE};
+    use cosmwasm_std::Binary;
+
+    let (mut deps, env) = setup();
+    set_config(&mut deps, &env, r#"{"unlock_messages":1}"#);
+    share(&mut deps, &env, "c0");
+    rate(&mut deps, &env, "aaa", "c0", 4);
+
+    let send = |deps: &mut Deps, meta: &[u8]| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("aaa", &[]),
+            ExecuteMsg::Message {
+                class_id: "c0".to_string(),
+                message: "hi".to_string(),
+                meta: Some(Binary::from(meta)),
+                reply_to: None,
+            },
+        )
+    };
+
+    // untyped meta is opaque, only the size is checked
+    send(&mut deps, b"\x00\x01 not json").unwrap();
+    assert!(send(&mut deps, &vec![b'x'; DEFAULT_MAX_META_SIZE as usize + 1]).is_err());
+    set_config(&mut deps, &env, r#"{"max_meta_size":16}"#);
+    assert!(send(&mut deps, &[b'x'; 17]).is_err());
+    set_config(
+        &mut deps,
+        &env,
+        r#"{"max_meta_size":1024,"typed_meta":true}"#,
+    );
+
+    // typed meta must match MessageMeta
+    assert!(send(&mut deps, b"\x00\x01 not json").is_err());
+    assert!(send(&mut deps, br#"{"video":"ipfs://x"}"#).is_err());
+    assert!(send(&mut deps, br#"{"image":" "}"#).is_err());
+    send(&mut deps, br#"{"image":"ipfs://x","mentions":["bbb"]}"#).unwrap();
+
+    let msgs: Vec<MessageResponse> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetNftMessages {
+                class_id: "c0".to_string(),
+                start_after: None,
+                limit: None,
+                order: None,
+                include_profiles: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let decoded = msgs.into_iter().map(|m| m.meta_decoded).collect::<Vec<_>>();
+    assert_eq!(
+        decoded,
+        vec![
+            None,
+            Some(MessageMeta {
+                image: Some("ipfs://x".to_string()),
+                link: None,
+                mentions: Some(vec!["bbb".to_string()]),
+            })
+        ]
+    );
+}
+
+#[test]
+fn read_marker_stops_at_the_newest_notification() {
+    use crate::types::Notification;
+
+    let (mut deps, env) = setup();
+    set_config(&mut deps, &env, r#"{"unlock_messages":1}"#);
+    share(&mut deps, &env, "c0");
+    for user in ["author", "replier"] {
+        rate(&mut deps, &env, user, "c0", 4);
+    }
+    let parent = post(&mut deps, &env, "author", "c0", "first");
+    let reply = |deps: &mut Deps| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("replier", &[]),
+            ExecuteMsg::Message {
+                class_id: "c0".to_string(),
+                message: "reply".to_string(),
+                meta: None,
+                reply_to: Some(parent),
+            },
+        )
+        .unwrap();
+    };
+    reply(&mut deps);
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("author", &[]),
+        ExecuteMsg::MarkNotificationsRead { up_to: u64::MAX },
+    )
+    .unwrap();
+    reply(&mut deps);
+
+    let inbox: Vec<Notification> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetNotifications {
+                addr: Addr::unchecked("author"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let read = inbox.iter().map(|n| n.read).collect::<Vec<_>>();
+    assert_eq!(read, vec![Some(false), Some(true)]);
+}
+
+#[test]
+fn direct_messages_and_blocking() {
+    use crate::types::{Conversation, DirectMessage};
+    use cosmwasm_std::Binary;
+
+    let (mut deps, env) = setup();
+    set_config(&mut deps, &env, r#"{"unlock_dms":1}"#);
+    share(&mut deps, &env, "c0");
+    rate(&mut deps, &env, "aaa", "c0", 4);
+    rate(&mut deps, &env, "bbb", "c0", 3);
+
+    let dm = |deps: &mut Deps, from: &str, to: &str, class_id: Option<&str>| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(from, &[]),
+            ExecuteMsg::SendDirectMessage {
+                to: Addr::unchecked(to),
+                class_id: class_id.map(str::to_string),
+                content: Binary::from(b"gm"),
+            },
+        )
+    };
+    let run = |deps: &mut Deps, sender: &str, msg: ExecuteMsg| {
+        execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg)
+    };
+
+    assert!(dm(&mut deps, "ccc", "aaa", None).is_err());
+    assert!(dm(&mut deps, "aaa", "aaa", None).is_err());
+    assert!(dm(&mut deps, "aaa", "bbb", Some("nope")).is_err());
+    dm(&mut deps, "aaa", "bbb", None).unwrap();
+    dm(&mut deps, "bbb", "aaa", None).unwrap();
+    dm(&mut deps, "aaa", "bbb", Some("c0")).unwrap();
+
+    // one thread per pair & NFT, whichever side asks
+    let thread = |deps: &Deps, a: &str, b: &str, class_id: Option<&str>| -> Vec<String> {
+        let msgs: Vec<DirectMessage> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetConversation {
+                    a: Addr::unchecked(a),
+                    b: Addr::unchecked(b),
+                    class_id: class_id.map(str::to_string),
+                    start_after: None,
+                    limit: None,
+                    order: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        msgs.into_iter().map(|m| m.from.to_string()).collect()
+    };
+    assert_eq!(thread(&deps, "aaa", "bbb", None), vec!["aaa", "bbb"]);
+    assert_eq!(thread(&deps, "bbb", "aaa", None), vec!["aaa", "bbb"]);
+    assert_eq!(thread(&deps, "bbb", "aaa", Some("c0")), vec!["aaa"]);
+
+    let convos: Vec<Conversation> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetConversations {
+                addr: Addr::unchecked("bbb"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let convos = convos
+        .into_iter()
+        .map(|c| (c.with.to_string(), c.class_id, c.count))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        convos,
+        vec![
+            ("aaa".to_string(), None, 2),
+            ("aaa".to_string(), Some("c0".to_string()), 1)
+        ]
+    );
+
+    // blocking is one way & only stops new messages to the blocker
+    assert!(run(
+        &mut deps,
+        "bbb",
+        ExecuteMsg::BlockUser {
+            addr: Addr::unchecked("bbb"),
+        },
+    )
+    .is_err());
+    run(
+        &mut deps,
+        "bbb",
+        ExecuteMsg::BlockUser {
+            addr: Addr::unchecked("aaa"),
+        },
+    )
+    .unwrap();
+    assert!(dm(&mut deps, "aaa", "bbb", None).is_err());
+    dm(&mut deps, "bbb", "aaa", None).unwrap();
+    let blocked: Vec<Addr> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetBlockedUsers {
+                addr: Addr::unchecked("bbb"),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(blocked, vec![Addr::unchecked("aaa")]);
+
+    run(
+        &mut deps,
+        "bbb",
+        ExecuteMsg::UnblockUser {
+            addr: Addr::unchecked("aaa"),
+        },
+    )
+    .unwrap();
+    dm(&mut deps, "aaa", "bbb", None).unwrap();
+    assert_eq!(thread(&deps, "aaa", "bbb", None).len(), 4);
+}
+
+fn save(deps: &mut Deps, env: &Env, sender: &str, class_id: &str, collection: Option<&str>) {
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(sender, &[coin(1, "ustars")]),
+        ExecuteMsg::Save {
+            class_id: class_id.to_string(),
+            collection: collection.map(str::to_string),
+            note: None,
+        },
+    )
+    .unwrap();
+}
+
+fn user_collections(deps: &Deps, env: &Env, addr: &str) -> Vec<(String, u64)> {
+    let collections: Vec<crate::types::SavedCollection> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetUserCollections {
+                addr: Addr::unchecked(addr),
+                start_after: None,
+                limit: None,
+                viewer: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    collections.into_iter().map(|c| (c.name, c.count)).collect()
+}
+
+#[test]
+fn saved_collections_lifecycle() {
+    use crate::types::{SavedNft, MAX_SAVED_PER_COLLECTION};
+
+    let (mut deps, env) = setup();
+    for class_id in ["c0", "c1", "c2"] {
+        share(&mut deps, &env, class_id);
+    }
+    let run = |deps: &mut Deps, msg: ExecuteMsg| {
+        execute(deps.as_mut(), env.clone(), mock_info("saver", &[]), msg)
+    };
+
+    // named collections need creating first, names are unique per user
+    assert!(execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("saver", &[coin(1, "ustars")]),
+        ExecuteMsg::Save {
+            class_id: "c0".to_string(),
+            collection: Some("To buy".to_string()),
+            note: None,
+        },
+    )
+    .is_err());
+    let create = ExecuteMsg::CreateCollection {
+        name: "To buy".to_string(),
+    };
+    run(&mut deps, create.clone()).unwrap();
+    assert!(run(&mut deps, create).is_err());
+
+    save(&mut deps, &env, "saver", "c0", None);
+    save(&mut deps, &env, "saver", "c1", Some("To buy"));
+    save(&mut deps, &env, "saver", "c2", Some("To buy"));
+    assert_eq!(
+        user_collections(&deps, &env, "saver"),
+        vec![("Favorites".to_string(), 1), ("To buy".to_string(), 2)]
+    );
+
+    // moving keeps the counts in step, & can't land on a duplicate
+    run(
+        &mut deps,
+        ExecuteMsg::MoveSaved {
+            class_id: "c1".to_string(),
+            from: "To buy".to_string(),
+            to: "Favorites".to_string(),
+        },
+    )
+    .unwrap();
+    save(&mut deps, &env, "saver", "c0", Some("To buy"));
+    assert!(run(
+        &mut deps,
+        ExecuteMsg::MoveSaved {
+            class_id: "c0".to_string(),
+            from: "To buy".to_string(),
+            to: "Favorites".to_string(),
+        },
+    )
+    .is_err());
+
+    // renaming carries the saved entries along
+    run(
+        &mut deps,
+        ExecuteMsg::RenameCollection {
+            name: "To buy".to_string(),
+            new_name: "Wishlist".to_string(),
+        },
+    )
+    .unwrap();
+    let wishlist: Vec<SavedNft> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetCollectionNfts {
+                addr: Addr::unchecked("saver"),
+                collection: "Wishlist".to_string(),
+                start_after: None,
+                limit: None,
+                viewer: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let ids = wishlist
+        .into_iter()
+        .map(|s| s.nft.class_id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec!["c0".to_string(), "c2".to_string()]);
+
+    // deleting only drops saves that no other collection holds
+    run(
+        &mut deps,
+        ExecuteMsg::DeleteCollection {
+            name: "Wishlist".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        user_collections(&deps, &env, "saver"),
+        vec![("Favorites".to_string(), 2)]
+    );
+    let count = |class_id: &str| -> u64 {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetNftSaveCount {
+                    class_id: class_id.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!((count("c0"), count("c1"), count("c2")), (1, 1, 0));
+
+    // collections are capped
+    for i in 0..MAX_SAVED_PER_COLLECTION {
+        let class_id = format!("full{}", i);
+        share(&mut deps, &env, &class_id);
+        if i >= 2 {
+            save(&mut deps, &env, "saver", &class_id, None);
+        }
+    }
+    share(&mut deps, &env, "extra");
+    assert!(execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("saver", &[coin(1, "ustars")]),
+        ExecuteMsg::Save {
+            class_id: "extra".to_string(),
+            collection: None,
+            note: None,
+        },
+    )
+    .is_err());
+}
+
+#[test]
+fn repeat_saves_count_once_and_most_saved_is_ordered() {
+    use crate::state::{STATS, USER_STATS};
+    use crate::types::{ListKind, ListSort, RateCount};
+
+    let (mut deps, env) = setup();
+    for class_id in ["c0", "c1", "c2"] {
+        share(&mut deps, &env, class_id);
+    }
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("s0", &[]),
+        ExecuteMsg::CreateCollection {
+            name: "Later".to_string(),
+        },
+    )
+    .unwrap();
+    // same user, same NFT, again & in another collection
+    save(&mut deps, &env, "s0", "c1", None);
+    save(&mut deps, &env, "s0", "c1", None);
+    save(&mut deps, &env, "s0", "c1", Some("Later"));
+    save(&mut deps, &env, "s1", "c1", None);
+    save(&mut deps, &env, "s0", "c2", None);
+
+    let storage = deps.as_ref().storage;
+    assert_eq!(STATS.load(storage).unwrap().saves, 3);
+    let st = USER_STATS.load(storage, Addr::unchecked("s0")).unwrap();
+    assert_eq!(st.saves, 2);
+
+    let list = |sort: ListSort| -> Vec<(String, u64)> {
+        let list: Vec<(Nft, RateCount)> = from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetList {
+                    kind: ListKind::MostSaved,
+                    sort,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        list.into_iter()
+            .map(|(nft, rc)| (nft.class_id, rc.sum))
+            .collect()
+    };
+    assert_eq!(
+        list(ListSort::Highest),
+        vec![("c1".to_string(), 2), ("c2".to_string(), 1)]
+    );
+    assert_eq!(
+        list(ListSort::Lowest),
+        vec![("c2".to_string(), 1), ("c1".to_string(), 2)]
+    );
+}
+
+#[test]
+fn saved_visibility_and_galleries() {
+    use crate::types::{Gallery, Visibility};
+
+    let (mut deps, env) = setup();
+    share(&mut deps, &env, "c0");
+    save(&mut deps, &env, "curator", "c0", None);
+
+    let run = |deps: &mut Deps, sender: &str, msg: ExecuteMsg| {
+        execute(deps.as_mut(), env.clone(), mock_info(sender, &[]), msg)
+    };
+    let saved = |deps: &Deps, viewer: Option<&str>| {
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetUserNftSaved {
+                addr: Addr::unchecked("curator"),
+                start_after: None,
+                limit: None,
+                viewer: viewer.map(Addr::unchecked),
+            },
+        )
+        .map(|bin| from_json::<Vec<Nft>>(bin).unwrap().len())
+    };
+
+    // public by default, followers only hides it from everyone else
+    assert_eq!(saved(&deps, None).unwrap(), 1);
+    run(
+        &mut deps,
+        "curator",
+        ExecuteMsg::SetSavedVisibility {
+            visibility: Visibility::FollowersOnly,
+        },
+    )
+    .unwrap();
+    assert!(saved(&deps, None).is_err());
+    assert!(saved(&deps, Some("fan")).is_err());
+    assert_eq!(saved(&deps, Some("curator")).unwrap(), 1);
+    run(
+        &mut deps,
+        "fan",
+        ExecuteMsg::Follow {
+            addr: Addr::unchecked("curator"),
+        },
+    )
+    .unwrap();
+    assert_eq!(saved(&deps, Some("fan")).unwrap(), 1);
+
+    // only existing collections with a title can be published
+    let publish = |deps: &mut Deps, name: &str, title: &str| {
+        run(
+            deps,
+            "curator",
+            ExecuteMsg::PublishCollection {
+                name: name.to_string(),
+                title: title.to_string(),
+                description: None,
+            },
+        )
+    };
+    assert!(publish(&mut deps, "Nope", "Picks").is_err());
+    assert!(publish(&mut deps, "Favorites", " ").is_err());
+    publish(&mut deps, "Favorites", "Picks").unwrap();
+
+    let gallery = |deps: &Deps| -> Option<Gallery> {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetGallery {
+                    owner: Addr::unchecked("curator"),
+                    name: "Favorites".to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let save_gallery = ExecuteMsg::SaveGallery {
+        owner: Addr::unchecked("curator"),
+        name: "Favorites".to_string(),
+    };
+    // saving twice counts once
+    run(&mut deps, "fan", save_gallery.clone()).unwrap();
+    run(&mut deps, "fan", save_gallery.clone()).unwrap();
+    assert_eq!(gallery(&deps).unwrap().saves, 1);
+
+    // re-publishing updates the text and keeps the saves
+    publish(&mut deps, "Favorites", "Best picks").unwrap();
+    let g = gallery(&deps).unwrap();
+    assert_eq!((g.title.as_str(), g.saves), ("Best picks", 1));
+
+    let fan_galleries = |deps: &Deps| -> Vec<Gallery> {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetUserSavedGalleries {
+                    addr: Addr::unchecked("fan"),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(fan_galleries(&deps).len(), 1);
+
+    run(
+        &mut deps,
+        "fan",
+        ExecuteMsg::UnsaveGallery {
+            owner: Addr::unchecked("curator"),
+            name: "Favorites".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(gallery(&deps).unwrap().saves, 0);
+    run(&mut deps, "fan", save_gallery.clone()).unwrap();
+
+    // unpublished galleries drop out of saved lists and can't be saved
+    run(
+        &mut deps,
+        "curator",
+        ExecuteMsg::UnpublishCollection {
+            name: "Favorites".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(gallery(&deps), None);
+    assert!(fan_galleries(&deps).is_empty());
+    assert!(run(&mut deps, "fan", save_gallery).is_err());
+}
+
+#[test]
+fn storage_namespaces_are_distinct() {
+    let state = include_str!("state.rs");
+    let mut seen = std::collections::HashSet::new();
+    for part in state.split("::new(\"").skip(1) {
+        let namespace = part.split('"').next().unwrap();
+        assert!(seen.insert(namespace), "namespace {} used twice", namespace);
+    }
+    assert!(seen.len() > 40);
+}
+
+#[test]
+fn follows_and_feed() {
+    use crate::state::USER_STATS;
+    use crate::types::{Activity, ActivityKind, UserStatsResponse};
+
+    let (mut deps, env) = setup();
+    set_config(&mut deps, &env, r#"{"unlock_messages":1}"#);
+    for class_id in ["c0", "c1"] {
+        share(&mut deps, &env, class_id);
+    }
+    let follow = |deps: &mut Deps, sender: &str, addr: &str| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender, &[]),
+            ExecuteMsg::Follow {
+                addr: Addr::unchecked(addr),
+            },
+        )
+    };
+    let user = |deps: &Deps, addr: &str| -> Option<UserStatsResponse> {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetUser {
+                    addr: Addr::unchecked(addr),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    let feed = |deps: &Deps, limit: u32, start_after: Option<u64>| -> Vec<Activity> {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetFeed {
+                    addr: Addr::unchecked("fan"),
+                    start_after,
+                    limit: Some(limit),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    assert!(follow(&mut deps, "fan", "fan").is_err());
+    follow(&mut deps, "fan", "curator").unwrap();
+    assert!(follow(&mut deps, "fan", "curator").is_err());
+
+    // being followed doesn't create stats, the counts still show
+    assert!(!USER_STATS.has(deps.as_ref().storage, Addr::unchecked("curator")));
+    let curator = user(&deps, "curator").unwrap();
+    assert_eq!(curator.follows.followers, 1);
+    assert_eq!(curator.stats.ratings, 0);
+    assert_eq!(user(&deps, "fan").unwrap().follows.following, 1);
+
+    rate(&mut deps, &env, "curator", "c0", 4);
+    rate(&mut deps, &env, "curator", "c1", 2);
+    let msg_id = post(&mut deps, &env, "curator", "c0", "look");
+    rate(&mut deps, &env, "stranger", "c0", 5);
+
+    let kinds = feed(&deps, 10, None)
+        .into_iter()
+        .map(|a| (a.kind, a.class_id))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            (ActivityKind::Message, "c0".to_string()),
+            (ActivityKind::Rate, "c1".to_string()),
+            (ActivityKind::Rate, "c0".to_string()),
+        ]
+    );
+
+    // a long run of removed items doesn't hide what sits behind it
+    for i in 0..12 {
+        let id = post(&mut deps, &env, "curator", "c1", &format!("oops {}", i));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("curator", &[]),
+            ExecuteMsg::DeleteOwnMessage { id },
+        )
+        .unwrap();
+    }
+    let page = feed(&deps, 2, None);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page[0].msg_id, Some(msg_id));
+    let next = feed(&deps, 2, page[1].id);
+    assert_eq!(next.len(), 1);
+    assert_eq!(next[0].class_id, "c0");
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("fan", &[]),
+        ExecuteMsg::Unfollow {
+            addr: Addr::unchecked("curator"),
+        },
+    )
+    .unwrap();
+    assert_eq!(feed(&deps, 10, None), vec![]);
+    assert_eq!(user(&deps, "curator").unwrap().follows.followers, 0);
+    assert_eq!(user(&deps, "fan"), None);
+}
+
+#[test]
+fn profiles_hold_unique_names_and_valid_avatars() {
+    use crate::types::{Avatar, CollectionStatus, Profile};
+
+    let (mut deps, env) = setup();
+    share(&mut deps, &env, "c0");
+
+    let set = |deps: &mut Deps, sender: &str, name: Option<&str>, avatar: Option<Avatar>| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender, &[]),
+            ExecuteMsg::SetProfile {
+                display_name: name.map(str::to_string),
+                bio: None,
+                avatar,
+            },
+        )
+    };
+    let resolve = |deps: &Deps, name: &str| -> Option<Addr> {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::ResolveName {
+                    name: name.to_string(),
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+
+    // names are unique ignoring case, and ASCII only
+    set(&mut deps, "aaa", Some("Alice"), None).unwrap();
+    assert!(set(&mut deps, "bbb", Some("alice"), None).is_err());
+    assert!(set(&mut deps, "bbb", Some("Аlice"), None).is_err());
+    assert!(set(&mut deps, "bbb", Some("al ice"), None).is_err());
+    assert_eq!(resolve(&deps, "ALICE"), Some(Addr::unchecked("aaa")));
+
+    // renaming or clearing frees the old name
+    set(&mut deps, "aaa", Some("Alicia"), None).unwrap();
+    assert_eq!(resolve(&deps, "alice"), None);
+    set(&mut deps, "bbb", Some("alice"), None).unwrap();
+    set(&mut deps, "aaa", None, None).unwrap();
+    assert_eq!(resolve(&deps, "alicia"), None);
+    assert_eq!(resolve(&deps, "Alice"), Some(Addr::unchecked("bbb")));
+
+    // avatars point at shared NFTs, or tokens that could be shared
+    let shared = |class_id: &str| {
+        Some(Avatar::Shared {
+            class_id: class_id.to_string(),
+        })
+    };
+    let token = |contract_addr: &str, id: &str| {
+        Some(Avatar::Token {
+            token: TokenUri {
+                contract_addr: Addr::unchecked(contract_addr),
+                id: id.to_string(),
+                data_uri: None,
+            },
+        })
+    };
+    assert!(set(&mut deps, "aaa", None, shared("nope")).is_err());
+    set(&mut deps, "aaa", None, shared("c0")).unwrap();
+    assert!(set(&mut deps, "aaa", None, token("collection", " ")).is_err());
+    set(&mut deps, "aaa", None, token("collection", "7")).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetCollectionStatus {
+            contract_addr: "collection".to_string(),
+            status: Some(CollectionStatus::Denied),
+        },
+    )
+    .unwrap();
+    assert!(set(&mut deps, "aaa", None, token("collection", "8")).is_err());
+
+    let profile: Option<Profile> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetProfile {
+                addr: Addr::unchecked("aaa"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(profile.unwrap().avatar, token("collection", "7"));
+}
+
+#[test]
+fn reputation_settles_and_unlocks() {
+    use crate::state::USER_STATS;
+    use crate::types::{Unlock, UserStatsResponse, REPUTATION_ACCURATE, REPUTATION_SHARE_BONUS};
+
+    let (mut deps, env) = setup();
+    set_config(
+        &mut deps,
+        &env,
+        r#"{"unlock_messages":100,"rep_unlock_messages":10,"rep_unlock_graffiti":10}"#,
+    );
+    share(&mut deps, &env, "c0");
+    share(&mut deps, &env, "c1");
+    // the sharer needs a stats row to be credited
+    rate(&mut deps, &env, OWNER, "c1", 3);
+
+    // 5 early raters on the average, 5 far off, then 15 late raters: average is 4 stars
+    let rater = |i: u32| format!("r{:02}", i);
+    for i in 0..25 {
+        let v = match i {
+            0..=4 => 4,
+            5..=9 => 1,
+            _ => 5,
+        };
+        rate(&mut deps, &env, &rater(i), "c0", v);
+    }
+
+    let reputation = |deps: &Deps, addr: &str| {
+        USER_STATS
+            .load(deps.as_ref().storage, Addr::unchecked(addr))
+            .unwrap()
+            .reputation
+            .unwrap_or_default()
+    };
+    assert_eq!(reputation(&deps, "r00"), REPUTATION_ACCURATE);
+    assert_eq!(reputation(&deps, "r05"), 0);
+    assert_eq!(reputation(&deps, "r10"), 0);
+    assert_eq!(reputation(&deps, OWNER), REPUTATION_SHARE_BONUS);
+
+    // settles once only
+    rate(&mut deps, &env, "r25", "c0", 4);
+    assert_eq!(reputation(&deps, "r00"), REPUTATION_ACCURATE);
+
+    // reputation stands in for the missing ratings
+    post(&mut deps, &env, "r00", "c0", "called it");
+    assert!(execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("r05", &[]),
+        ExecuteMsg::Message {
+            class_id: "c0".to_string(),
+            message: "nope".to_string(),
+            meta: None,
+            reply_to: None,
+        },
+    )
+    .is_err());
+
+    let user: UserStatsResponse = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetUser {
+                addr: Addr::unchecked("r00"),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    for unlock in [Unlock::Messages, Unlock::Graffiti] {
+        let progress = user.progress.iter().find(|p| p.unlock == unlock).unwrap();
+        assert_eq!(progress.reputation_required, Some(10));
+        assert!(progress.unlocked);
+    }
+    let share = user
+        .progress
+        .iter()
+        .find(|p| p.unlock == Unlock::Share)
+        .unwrap();
+    assert_eq!(share.reputation_required, None);
+    assert!(!share.unlocked);
+}
+
+#[test]
+fn lowering_an_unlock_awards_badges() {
+    use crate::types::ConfigHr;
+
+    let (mut deps, env) = setup();
+    for class_id in ["c0", "c1"] {
+        share(&mut deps, &env, class_id);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("rater", &[]),
+            ExecuteMsg::Rate {
+                class_id: class_id.to_string(),
+                v: 4,
+            },
+        )
+        .unwrap();
+    }
+
+    let config: ConfigHr = from_json(r#"{"unlock_messages":2}"#).unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(OWNER, &[]),
+        ExecuteMsg::ChangeConfig {
+            config: Box::new(config),
+        },
+    )
+    .unwrap();
+    assert!(res.events.iter().any(|e| e.ty == "badge"
+        && e.attributes
+            .iter()
+            .any(|a| a.key == "badge" && a.value == "messages_unlocked")));
+}
+
+#[test]
+fn current_streak_board_drops_broken_streaks() {
+    use crate::types::{UserMetric, CALENDAR_DAY_IN_SECONDS};
+
+    let (mut deps, mut env) = setup();
+    for (day, class_id) in ["c0", "c1"].iter().enumerate() {
+        if day > 0 {
+            env.block.time = env.block.time.plus_seconds(CALENDAR_DAY_IN_SECONDS);
+        }
+        share(&mut deps, &env, class_id);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("rater", &[]),
+            ExecuteMsg::Rate {
+                class_id: class_id.to_string(),
+                v: 4,
+            },
+        )
+        .unwrap();
+    }
+
+    let board = |deps: &Deps, env: &Env| -> Vec<(Addr, u64)> {
+        from_json(
+            query(
+                deps.as_ref(),
+                env.clone(),
+                QueryMsg::GetUserLeaderboard {
+                    metric: UserMetric::CurrentStreak,
+                    period: None,
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap()
+    };
+    assert_eq!(board(&deps, &env), vec![(Addr::unchecked("rater"), 2)]);
+
+    // still live the day after, gone once a whole day is missed
+    env.block.time = env.block.time.plus_seconds(CALENDAR_DAY_IN_SECONDS);
+    assert_eq!(board(&deps, &env).len(), 1);
+    env.block.time = env.block.time.plus_seconds(CALENDAR_DAY_IN_SECONDS);
+    assert_eq!(board(&deps, &env), vec![]);
+}
+
+#[test]
+fn migrate_seeds_boards_in_batches_once() {
+    use crate::state::{LEADERBOARD_SEED, USER_RANKS, USER_SCORES};
+    use crate::types::UserMetric;
+
+    let (mut deps, env) = setup();
+    share(&mut deps, &env, "c0");
+    for rater in ["r0", "r1", "r2"] {
+        rate(&mut deps, &env, rater, "c0", 4);
+    }
+    // older versions had no boards
+    for rater in ["r0", "r1", "r2"] {
+        let addr = Addr::unchecked(rater);
+        USER_SCORES.remove(deps.as_mut().storage, ("ratings".to_string(), addr.clone()));
+        USER_RANKS.remove(deps.as_mut().storage, ("ratings".to_string(), 1, addr));
+    }
+
+    set_old_version(&mut deps);
+    migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+    let seed = |deps: &mut Deps, sender: &str| {
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(sender, &[]),
+            ExecuteMsg::SeedLeaderboards { limit: Some(3) },
+        )
+    };
+    assert!(seed(&mut deps, "r0").is_err());
+    for done in ["false", "true"] {
+        let res = seed(&mut deps, OWNER).unwrap();
+        assert!(res
+            .attributes
+            .iter()
+            .any(|a| a.key == "done" && a.value == done));
+    }
+    assert!(seed(&mut deps, OWNER).is_err());
+
+    let board: Vec<(Addr, u64)> = from_json(
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetUserLeaderboard {
+                metric: UserMetric::Ratings,
+                period: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(board.len(), 3);
+
+    // already on the current version, nothing to redo
+    let res = migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+    assert!(!res.attributes.iter().any(|a| a.key == "nfts_reindexed"));
+    assert!(LEADERBOARD_SEED
+        .may_load(deps.as_ref().storage)
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn expired_day_boards_get_pruned() {
+    use crate::state::{USER_BOARD_WINDOWS, USER_SCORES};
+    use crate::types::{LeaderboardPeriod, UserMetric, CALENDAR_DAY_IN_SECONDS};
+    use cosmwasm_std::Order;
+
+    let (mut deps, mut env) = setup();
+    let now = env.block.time.seconds();
+    let yesterday = now - now % CALENDAR_DAY_IN_SECONDS;
+    share(&mut deps, &env, "c0");
+    rate(&mut deps, &env, "r0", "c0", 4);
+
+    env.block.time = env.block.time.plus_seconds(CALENDAR_DAY_IN_SECONDS);
+    rate(&mut deps, &env, "r1", "c0", 4);
+    let storage = deps.as_ref().storage;
+    assert_eq!(
+        USER_SCORES
+            .may_load(
+                storage,
+                (format!("ratings:d:{}", yesterday), Addr::unchecked("r0"))
+            )
+            .unwrap(),
+        None
+    );
+    let windows = USER_BOARD_WINDOWS
+        .prefix("ratings:d".to_string())
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<Result<Vec<u64>, _>>()
+        .unwrap();
+    assert_eq!(windows, vec![yesterday + CALENDAR_DAY_IN_SECONDS]);
+
+    let board = |metric: UserMetric| {
+        query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::GetUserLeaderboard {
+                metric,
+                period: Some(LeaderboardPeriod::Day),
+                start_after: None,
+                limit: None,
+            },
+        )
+    };
+    let day: Vec<(Addr, u64)> = from_json(board(UserMetric::Ratings).unwrap()).unwrap();
+    assert_eq!(day, vec![(Addr::unchecked("r1"), 1)]);
+    assert!(board(UserMetric::LongestStreak).is_err());
+}
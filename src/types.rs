@@ -5,13 +5,65 @@ use cosmwasm_std::{Addr, Binary, Coin};
 pub const DEFAULT_UNLOCK_MESSAGES: u64 = 5;
 pub const DEFAULT_UNLOCK_GRAFFITI: u64 = 25;
 pub const DEFAULT_UNLOCK_SHARES: u64 = 50;
+pub const DEFAULT_UNLOCK_DMS: u64 = 10;
 pub const DEFAULT_USER_MAX_SHARES: u64 = 50;
 pub const DEFAULT_RATE_DECAY: u64 = 2959200; // 3 days in seconds
+pub const DEFAULT_REPORT_THRESHOLD: u64 = 3;
+pub const DEFAULT_SLOT_DURATION: u64 = 3600; // 1 hour in seconds
+pub const DEFAULT_BOOST_DURATION: u64 = 21600; // 6 hours in seconds
+pub const DEFAULT_MESSAGE_EDIT_WINDOW: u64 = 900; // 15 minutes in seconds
 
+// reputation, scored once an NFT reaches REPUTATION_SETTLE_RATINGS
+pub const REPUTATION_EARLY_RATERS: u64 = 10;
+pub const REPUTATION_SETTLE_RATINGS: u64 = 25;
+pub const REPUTATION_ACCURATE: u64 = 10; // within half a star of the average
+pub const REPUTATION_CLOSE: u64 = 5; // within one star of the average
+pub const REPUTATION_SHARE_BONUS: u64 = 25; // shared NFT averages 4+ stars
+
+// badges
+pub const BADGE_RATINGS_MILESTONE: u64 = 100;
+pub const BADGE_TOP_SHARE_RANK: usize = 10;
+pub const BADGE_STREAK_DAYS: u64 = 30;
+// Users checked for badges when ChangeConfig lowers an unlock, the rest catch up on their next rating
+pub const BADGE_BACKFILL_LIMIT: usize = 200;
+
+// Text limits are counted in chars, not bytes
 pub const MAX_LEN_MESSAGE: usize = 141;
+pub const MAX_LEN_BANNED_WORD: usize = 32;
+pub const DEFAULT_MAX_META_SIZE: u64 = 1024;
+pub const MAX_LEN_META_URL: usize = 256;
+pub const MAX_META_MENTIONS: usize = 10;
+pub const MAX_LEN_REACTION: usize = 16;
+pub const MAX_REACTIONS: usize = 12;
+pub const DEFAULT_REACTIONS: [&str; 6] = ["👍", "❤️", "🔥", "😂", "😮", "😢"];
+pub const MAX_LEN_DIRECT_MESSAGE: usize = 1024; // bytes, DM content is an opaque blob
+pub const MAX_LEN_COLLECTION_NAME: usize = 32;
+pub const MAX_SAVED_COLLECTIONS: usize = 25;
+// Renaming & deleting rewrite every entry, so collections are capped
+pub const MAX_SAVED_PER_COLLECTION: u64 = 100;
+pub const MAX_LEN_GALLERY_TITLE: usize = 64;
+pub const MAX_LEN_GALLERY_DESCRIPTION: usize = 280;
+pub const MIN_LEN_DISPLAY_NAME: usize = 3;
+pub const MAX_LEN_DISPLAY_NAME: usize = 24;
+pub const MAX_LEN_BIO: usize = 160;
+pub const DEFAULT_SAVED_COLLECTION: &str = "Favorites";
 pub const MAX_LEN_ALL_TIME: usize = 100;
 pub const MAX_LEN_DAY: usize = 10;
-pub const DAY_IN_SECONDS: u64 = 986400; // 1 day in seconds
+// NOTE: NFT day list bucket, ~11.4 days rather than a calendar day. Kept as is so the
+// existing NFT_RATE_DAY_* keys stay valid, anything per-user uses CALENDAR_DAY_IN_SECONDS
+pub const DAY_IN_SECONDS: u64 = 986400;
+// Rating streaks, active days & day user boards
+pub const CALENDAR_DAY_IN_SECONDS: u64 = 86400;
+// Month user boards are fixed 30 day windows, not calendar months
+pub const LEADERBOARD_MONTH_IN_SECONDS: u64 = 30 * CALENDAR_DAY_IN_SECONDS;
+// Rows cleared from an expired day/month board each time that metric is bumped
+pub const BOARD_PRUNE_LIMIT: usize = 5;
+
+// queries
+pub const DEFAULT_LIMIT: u32 = 50;
+pub const MAX_LIMIT: u32 = 100;
+// Feeds only merge the first followed accounts, ordered by address
+pub const MAX_FEED_SOURCES: usize = 100;
 
 #[cw_serde]
 pub struct Config {
@@ -23,8 +75,36 @@ pub struct Config {
     pub unlock_messages: Option<u64>,
     pub unlock_graffiti: Option<u64>,
     pub unlock_share: Option<u64>,
+    pub unlock_dms: Option<u64>,
     pub max_shares: Option<u64>,
     pub rate_decay: Option<u64>,
+
+    // Reputation alternative to the ratings unlocks above, off when unset
+    pub rep_unlock_messages: Option<u64>,
+    pub rep_unlock_graffiti: Option<u64>,
+    pub rep_unlock_share: Option<u64>,
+
+    // Moderation
+    pub report_threshold: Option<u64>,
+    pub allowlist_only: Option<bool>,
+
+    // Rotation schedule
+    pub slot_duration: Option<u64>,
+    pub schedule_priority: Option<SchedulePriority>,
+
+    // Boost auctions, disabled until a min bid is set
+    pub boost_min_bid: Option<Coin>,
+    pub boost_duration: Option<u64>,
+
+    // Seconds after posting that authors can still edit, 0 disables edits
+    pub message_edit_window: Option<u64>,
+    // Max message length in chars, not bytes
+    pub max_message_len: Option<u64>,
+    // Max meta size in bytes, typed meta requires a MessageMeta JSON object
+    pub max_meta_size: Option<u64>,
+    pub typed_meta: Option<bool>,
+    // Reactions users can pick from, defaults to DEFAULT_REACTIONS
+    pub reactions: Option<Vec<String>>,
 }
 
 #[cw_serde]
@@ -37,8 +117,36 @@ pub struct ConfigHr {
     pub unlock_messages: Option<u64>,
     pub unlock_graffiti: Option<u64>,
     pub unlock_share: Option<u64>,
+    pub unlock_dms: Option<u64>,
     pub max_shares: Option<u64>,
     pub rate_decay: Option<u64>,
+
+    // Reputation alternative to the ratings unlocks above, off when unset
+    pub rep_unlock_messages: Option<u64>,
+    pub rep_unlock_graffiti: Option<u64>,
+    pub rep_unlock_share: Option<u64>,
+
+    // Moderation
+    pub report_threshold: Option<u64>,
+    pub allowlist_only: Option<bool>,
+
+    // Rotation schedule
+    pub slot_duration: Option<u64>,
+    pub schedule_priority: Option<SchedulePriority>,
+
+    // Boost auctions, disabled until a min bid is set
+    pub boost_min_bid: Option<Coin>,
+    pub boost_duration: Option<u64>,
+
+    // Seconds after posting that authors can still edit, 0 disables edits
+    pub message_edit_window: Option<u64>,
+    // Max message length in chars, not bytes
+    pub max_message_len: Option<u64>,
+    // Max meta size in bytes, typed meta requires a MessageMeta JSON object
+    pub max_meta_size: Option<u64>,
+    pub typed_meta: Option<bool>,
+    // Reactions users can pick from, defaults to DEFAULT_REACTIONS
+    pub reactions: Option<Vec<String>>,
 }
 
 #[cw_serde]
@@ -49,12 +157,135 @@ pub struct TotalStats {
     pub saves: u64,
 }
 
+/// Progress of filling the all time user boards after migrating from an older version
+#[cw_serde]
+pub struct LeaderboardSeed {
+    pub users_after: Option<Addr>,
+    pub users_done: bool,
+    pub messages_after: Option<u64>,
+    /// Newest message at migrate, later ones are already counted
+    pub messages_until: u64,
+}
+
 #[cw_serde]
+#[derive(Default)]
 pub struct UserStats {
     pub last_rate_ts: u64,
     pub ratings: u64,
     pub saves: u64,
     pub shares: u64,
+
+    /// Curation accuracy, see REPUTATION_* constants
+    pub reputation: Option<u64>,
+
+    // Rating streaks, counted in the same day buckets as the day lists
+    pub current_streak: Option<u64>,
+    pub longest_streak: Option<u64>,
+    pub active_days: Option<u64>,
+    pub last_active_day: Option<u64>,
+}
+
+/// Follow graph counters, stored apart from UserStats
+#[cw_serde]
+#[derive(Default)]
+pub struct FollowCounts {
+    pub followers: u64,
+    pub following: u64,
+}
+
+/// GetUser response, unlock progress is worked out at query time
+#[cw_serde]
+pub struct UserStatsResponse {
+    pub stats: UserStats,
+    pub follows: FollowCounts,
+    pub progress: Vec<UnlockProgress>,
+}
+
+#[cw_serde]
+pub enum Unlock {
+    Messages,
+    Graffiti,
+    Share,
+    DirectMessages,
+}
+
+#[cw_serde]
+pub struct UnlockProgress {
+    pub unlock: Unlock,
+    pub ratings: u64,
+    pub ratings_required: u64,
+    /// Unset when the reputation gate is off
+    pub reputation_required: Option<u64>,
+    pub unlocked: bool,
+}
+
+#[cw_serde]
+pub enum BadgeKind {
+    FirstRating,
+    MessagesUnlocked,
+    GraffitiUnlocked,
+    ShareUnlocked,
+    DirectMessagesUnlocked,
+    HundredRatings,
+    TopTenShare,
+    RatingStreak,
+}
+
+impl BadgeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BadgeKind::FirstRating => "first_rating",
+            BadgeKind::MessagesUnlocked => "messages_unlocked",
+            BadgeKind::GraffitiUnlocked => "graffiti_unlocked",
+            BadgeKind::ShareUnlocked => "share_unlocked",
+            BadgeKind::DirectMessagesUnlocked => "direct_messages_unlocked",
+            BadgeKind::HundredRatings => "hundred_ratings",
+            BadgeKind::TopTenShare => "top_ten_share",
+            BadgeKind::RatingStreak => "rating_streak",
+        }
+    }
+}
+
+/// Ranked user stats, see USER_RANKS
+#[cw_serde]
+pub enum UserMetric {
+    CurrentStreak,
+    LongestStreak,
+    ActiveDays,
+    Ratings,
+    Shares,
+    /// Distinct savers of NFTs the user shared, not counting themselves
+    SavesReceived,
+    Messages,
+}
+
+impl UserMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserMetric::CurrentStreak => "current_streak",
+            UserMetric::LongestStreak => "longest_streak",
+            UserMetric::ActiveDays => "active_days",
+            UserMetric::Ratings => "ratings",
+            UserMetric::Shares => "shares",
+            UserMetric::SavesReceived => "saves_received",
+            UserMetric::Messages => "messages",
+        }
+    }
+}
+
+/// Days are UTC calendar days, months are fixed 30 day windows.
+/// Streak metrics are all time only, asking for a window is an error.
+#[cw_serde]
+pub enum LeaderboardPeriod {
+    AllTime,
+    Day,
+    Month,
+}
+
+#[cw_serde]
+pub struct Badge {
+    pub kind: BadgeKind,
+    pub ts: u64,
 }
 
 #[cw_serde]
@@ -79,15 +310,258 @@ pub struct Nft {
 
     /// The queue place
     pub index: Option<u64>,
+
+    /// Taken down by the owner, skipped by queue & list queries
+    pub hidden: Option<bool>,
+
+    /// Who shared it, unset for NFTs shared before this was tracked
+    pub sharer: Option<Addr>,
+}
+
+/// A user's named folder of saved NFTs
+#[cw_serde]
+pub struct SavedCollection {
+    pub name: String,
+    pub ts: u64,
+    pub count: u64,
+}
+
+/// When & why an NFT was saved into a collection
+#[cw_serde]
+pub struct SavedMeta {
+    pub ts: u64,
+    pub note: Option<String>,
 }
 
+/// Who a user would like their saved lists shown to, a UI hint and not privacy.
+/// NOTE: All contract state is public and `viewer` is self-declared by the caller,
+/// anyone can read saved lists straight from storage or by passing the owner as `viewer`
+#[cw_serde]
+pub enum Visibility {
+    Public,
+    Private,
+    FollowersOnly,
+}
+
+/// A saved collection published as a curated gallery, always public
+#[cw_serde]
+pub struct Gallery {
+    pub owner: Addr,
+    pub collection: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub ts: u64,
+    pub saves: u64,
+}
+
+#[cw_serde]
+pub struct SavedNft {
+    pub nft: Nft,
+    pub meta: SavedMeta,
+}
+
+/// Feed record, rates are only loaded when asked for
+#[cw_serde]
+pub struct NftInfo {
+    pub nft: Nft,
+    pub rates: Option<RateCounts>,
+}
+
+pub type MessageId = u64;
+
 #[cw_serde]
 pub struct Message {
+    /// Storage key, always set in query responses
+    pub id: Option<MessageId>,
     pub ts: u64,
     pub class_id: String,
     pub message: String,
     pub from: Addr,
     pub meta: Option<Binary>,
+
+    /// Parent message, replies must be on the same NFT
+    pub reply_to: Option<MessageId>,
+    /// Aggregated reaction counts, kept in sync by React/Unreact
+    pub reactions: Option<Vec<ReactionCount>>,
+    /// Last time the author edited the message
+    pub edited_at: Option<u64>,
+}
+
+/// Message as returned by queries
+#[cw_serde]
+pub struct MessageResponse {
+    pub message: Message,
+    /// Decoded meta, set when meta matches MessageMeta
+    pub meta_decoded: Option<MessageMeta>,
+    /// Author profile, set when asked for
+    pub author: Option<Profile>,
+}
+
+/// Avatar can point at an NFT shared here, or any token
+#[cw_serde]
+pub enum Avatar {
+    Shared { class_id: String },
+    Token { token: TokenUri },
+}
+
+#[cw_serde]
+pub struct Profile {
+    /// Unique, case-insensitive
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub avatar: Option<Avatar>,
+    pub updated_ts: u64,
+}
+
+#[cw_serde]
+pub struct RatingInfo {
+    pub addr: Addr,
+    pub rate: Rate,
+    /// Only set when asked for
+    pub profile: Option<Profile>,
+}
+
+#[cw_serde]
+pub enum ActivityKind {
+    Share,
+    Rate,
+    Message,
+}
+
+/// Per-user activity log entry, merged into follower feeds
+#[cw_serde]
+pub struct Activity {
+    /// Storage key, always set in query responses
+    pub id: Option<u64>,
+    pub kind: ActivityKind,
+    pub from: Addr,
+    pub class_id: String,
+    pub ts: u64,
+    /// Rate value for ratings
+    pub v: Option<u8>,
+    pub msg_id: Option<MessageId>,
+}
+
+/// Content is opaque, clients encrypt it for the recipient before sending
+#[cw_serde]
+pub struct DirectMessage {
+    /// Storage key, always set in query responses
+    pub id: Option<u64>,
+    pub ts: u64,
+    pub from: Addr,
+    pub to: Addr,
+    pub class_id: Option<String>,
+    pub content: Binary,
+}
+
+/// One side's view of a DM thread, listed per user
+#[cw_serde]
+pub struct Conversation {
+    pub with: Addr,
+    pub class_id: Option<String>,
+    pub last_ts: u64,
+    pub count: u64,
+}
+
+#[cw_serde]
+pub enum NotificationKind {
+    Mention,
+    Reply,
+}
+
+#[cw_serde]
+pub struct Notification {
+    /// Storage key, always set in query responses
+    pub id: Option<u64>,
+    pub kind: NotificationKind,
+    pub msg_id: MessageId,
+    pub class_id: String,
+    pub from: Addr,
+    pub ts: u64,
+    /// Only set in query responses, based on the user's read marker
+    pub read: Option<bool>,
+}
+
+/// Known keys for Message.meta, unknown keys are rejected when typed meta is on
+#[cw_serde]
+pub struct MessageMeta {
+    pub image: Option<String>,
+    pub link: Option<String>,
+    pub mentions: Option<Vec<String>>,
+}
+
+#[cw_serde]
+pub struct ReactionCount {
+    pub reaction: String,
+    pub count: u64,
+}
+
+/// How pending NFTs are ordered for the "current" rotation
+#[cw_serde]
+pub enum SchedulePriority {
+    /// First shared, first shown
+    Fifo,
+    /// Owner shares jump ahead of everyone else
+    Owner,
+    /// Higher share fee paid jumps ahead
+    Fee,
+}
+
+/// An NFT waiting for its slot, `ts` is when it was queued
+#[cw_serde]
+pub struct PendingNft {
+    pub class_id: String,
+    pub ts: u64,
+}
+
+/// An NFT slot in the rotation, `start` is derived from block time
+#[cw_serde]
+pub struct ScheduledNft {
+    pub class_id: String,
+    pub start: u64,
+}
+
+/// Highest bid to feature an NFT for a boost window
+/// Windows are `boost_duration` long, aligned to block time: start = window * boost_duration
+/// Stored by `start`, so changing `boost_duration` doesn't remap past windows
+#[cw_serde]
+pub struct BoostBid {
+    pub class_id: String,
+    pub bidder: Addr,
+    pub amount: Coin,
+    pub window: u64,
+    pub start: u64,
+    pub end: u64,
+}
+
+/// Owner managed status for collection contracts & chain ids
+#[cw_serde]
+pub enum CollectionStatus {
+    Allowed,
+    Denied,
+}
+
+/// A single user's report against a message
+#[cw_serde]
+pub struct Report {
+    pub ts: u64,
+    pub reason: String,
+}
+
+/// Moderation record, a mute has an `until` and a ban does not
+#[cw_serde]
+pub struct UserBan {
+    pub ts: u64,
+    pub until: Option<u64>,
+}
+
+/// Where a purge of a banned user's content left off, messages are scanned before ratings
+#[cw_serde]
+#[derive(Default)]
+pub struct PurgeCursor {
+    pub messages_after: Option<u64>,
+    pub messages_done: bool,
+    pub ratings_after: Option<(String, Addr)>,
 }
 
 #[cw_serde]
@@ -112,15 +586,34 @@ pub struct RateCounts {
     pub day: RateCount,
 }
 
+/// User actions that can be paused by the owner
+#[cw_serde]
+pub enum Action {
+    Rate,
+    Share,
+    Message,
+    Save,
+    Boost,
+    DirectMessage,
+}
+
 #[cw_serde]
 pub enum ListSort {
     Highest,
     Lowest,
 }
 
+#[cw_serde]
+pub enum ListOrder {
+    Ascending,
+    Descending,
+}
+
 #[cw_serde]
 pub enum ListKind {
     All,
     Day,
     Month,
+    /// Ranked by distinct savers, `RateCount.sum` holds the save count
+    MostSaved,
 }